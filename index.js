@@ -34,15 +34,16 @@ js
       reader.get_dataset_attributes("volume")
         .then(data_attrs => {
           console.log("volume attributes:" + data_attrs.get_dimensions());
-          return reader.read_block("volume", data_attrs, [0, 0, 0].map(BigInt));
+          return reader.read_block("volume", data_attrs, [0, 0, 0]);
         })
         .then(block => {
           console.log("block:" + (block == null));
           console.log(block);
-          console.log(block.get_size());
-          console.log(block.get_grid_position());
-          console.log(block.get_data());
-          console.log(block.get_num_elements());
+          console.log(block.dtype);
+          console.log(block.size);
+          console.log(block.gridPosition);
+          console.log(block.data);
+          console.log(block.numElements);
         })
     ])
 	});
\ No newline at end of file
@@ -0,0 +1,146 @@
+//! Shim exposing the async reader traits with futures 0.1 boxed futures, for
+//! users that have not yet migrated to `std::future`.
+
+use futures01::Future as Future01;
+
+use super::*;
+
+
+pub type BoxFuture01<T> = Box<dyn Future01<Item = T, Error = Error>>;
+
+/// Wraps a `std::future` into a boxed futures 0.1 future.
+fn compat<F, T>(future: F) -> BoxFuture01<T>
+        where F: Future<Output = Result<T, Error>> + 'static, T: 'static {
+    Box::new(Box::pin(future).compat())
+}
+
+/// The futures 0.1 interface `N5AsyncReader` had before migrating to
+/// `std::future`. Implemented for all clonable async readers.
+pub trait N5AsyncReader01 {
+    fn get_version(&self) -> BoxFuture01<n5::Version>;
+
+    fn get_dataset_attributes(&self, path_name: &str) -> BoxFuture01<n5::DatasetAttributes>;
+
+    fn exists(&self, path_name: &str) -> BoxFuture01<bool>;
+
+    fn dataset_exists(&self, path_name: &str) -> BoxFuture01<bool>;
+
+    fn read_block<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> BoxFuture01<Option<VecDataBlock<T>>>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: ReflectedType;
+
+    fn list(&self, path_name: &str) -> BoxFuture01<Vec<String>>;
+
+    fn list_attributes(&self, path_name: &str) -> BoxFuture01<serde_json::Value>;
+}
+
+impl<R> N5AsyncReader01 for R where R: N5AsyncReader + Clone + 'static {
+    fn get_version(&self) -> BoxFuture01<n5::Version> {
+        let reader = self.clone();
+        compat(async move { reader.get_version().await })
+    }
+
+    fn get_dataset_attributes(&self, path_name: &str) -> BoxFuture01<n5::DatasetAttributes> {
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        compat(async move { reader.get_dataset_attributes(&path_name).await })
+    }
+
+    fn exists(&self, path_name: &str) -> BoxFuture01<bool> {
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        compat(async move { reader.exists(&path_name).await })
+    }
+
+    fn dataset_exists(&self, path_name: &str) -> BoxFuture01<bool> {
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        compat(async move { reader.dataset_exists(&path_name).await })
+    }
+
+    fn read_block<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> BoxFuture01<Option<VecDataBlock<T>>>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: ReflectedType,
+    {
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let data_attrs = data_attrs.clone();
+        compat(async move {
+            reader.read_block(&path_name, &data_attrs, grid_position).await
+        })
+    }
+
+    fn list(&self, path_name: &str) -> BoxFuture01<Vec<String>> {
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        compat(async move { reader.list(&path_name).await })
+    }
+
+    fn list_attributes(&self, path_name: &str) -> BoxFuture01<serde_json::Value> {
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        compat(async move { reader.list_attributes(&path_name).await })
+    }
+}
+
+/// The futures 0.1 interface of `N5AsyncEtagReader`.
+pub trait N5AsyncEtagReader01 {
+    fn block_etag(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> BoxFuture01<Option<String>>;
+
+    fn read_block_with_etag<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> BoxFuture01<Option<(VecDataBlock<T>, Option<String>)>>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: ReflectedType;
+}
+
+impl<R> N5AsyncEtagReader01 for R where R: N5AsyncEtagReader + Clone + 'static {
+    fn block_etag(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> BoxFuture01<Option<String>> {
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let data_attrs = data_attrs.clone();
+        compat(async move {
+            reader.block_etag(&path_name, &data_attrs, grid_position).await
+        })
+    }
+
+    fn read_block_with_etag<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> BoxFuture01<Option<(VecDataBlock<T>, Option<String>)>>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: ReflectedType,
+    {
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let data_attrs = data_attrs.clone();
+        compat(async move {
+            reader.read_block_with_etag(&path_name, &data_attrs, grid_position).await
+        })
+    }
+}
@@ -0,0 +1,276 @@
+//! Reading regions of datasets at reduced resolution, for overviews of
+//! datasets without a precomputed pyramid. Blocks are reduced as they
+//! arrive, so the full resolution region is never assembled.
+
+use futures::stream::{
+    FuturesUnordered,
+    TryStreamExt,
+};
+
+use super::*;
+
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "Promise<DownsampledRegion>")]
+    pub type PromiseDownsampledRegion;
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Downsampling {
+    /// The mean of each cell, rounded for integer data types.
+    Mean = 0,
+    /// The most frequent value of each cell, the smallest on ties, which
+    /// preserves labels.
+    Mode = 1,
+    /// The maximum of each cell.
+    Max = 2,
+    /// The first voxel of each cell, which is fastest.
+    Stride = 3,
+}
+
+/// A region of a dataset reduced by integer factors. Each element summarizes
+/// a cell of up to `factors` voxels, in column-major order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DownsampledRegion<T> {
+    /// Offset of the region, at full resolution.
+    pub offset: Vec<u64>,
+    /// Size of the downsampled data.
+    pub size: Vec<u64>,
+    pub factors: Vec<u64>,
+    pub data: Vec<T>,
+}
+
+/// Running reductions of each cell.
+enum Accumulator<T> {
+    Mean(Vec<(f64, u64)>),
+    Mode(Vec<Vec<(T, u64)>>),
+    Max(Vec<Option<T>>),
+    Stride(Vec<Option<T>>),
+}
+
+impl<T: Numeric> Accumulator<T> {
+    fn new(method: Downsampling, len: usize) -> Accumulator<T> {
+        match method {
+            Downsampling::Mean => Accumulator::Mean(vec![(0., 0); len]),
+            Downsampling::Mode => Accumulator::Mode(vec![Vec::new(); len]),
+            Downsampling::Max => Accumulator::Max(vec![None; len]),
+            Downsampling::Stride => Accumulator::Stride(vec![None; len]),
+        }
+    }
+
+    /// Add a voxel to a cell, where `first` is whether it is the first
+    /// voxel of its cell.
+    fn add(&mut self, cell: usize, first: bool, value: T) {
+        match self {
+            Accumulator::Mean(cells) => {
+                cells[cell].0 += value.to_f64();
+                cells[cell].1 += 1;
+            },
+            Accumulator::Mode(cells) => {
+                match cells[cell].iter_mut().find(|(v, _)| *v == value) {
+                    Some((_, count)) => *count += 1,
+                    None => cells[cell].push((value, 1)),
+                }
+            },
+            Accumulator::Max(cells) => {
+                if cells[cell].map_or(true, |max| value > max) {
+                    cells[cell] = Some(value);
+                }
+            },
+            Accumulator::Stride(cells) => {
+                if first {
+                    cells[cell] = Some(value);
+                }
+            },
+        }
+    }
+
+    /// The reduction of every cell. Cells without voxels are zero.
+    fn finish(self) -> Vec<T> {
+        match self {
+            Accumulator::Mean(cells) => cells.into_iter()
+                .map(|(sum, count)| if count > 0 { T::from_f64(sum / count as f64) } else { T::default() })
+                .collect(),
+            Accumulator::Mode(cells) => cells.into_iter()
+                .map(|counts| counts.into_iter()
+                    .fold(None, |mode: Option<(T, u64)>, (value, count)| match mode {
+                        Some((mode_value, mode_count)) if mode_count > count
+                            || (mode_count == count && mode_value < value) => Some((mode_value, mode_count)),
+                        _ => Some((value, count)),
+                    })
+                    .map(|(value, _)| value)
+                    .unwrap_or_default())
+                .collect(),
+            Accumulator::Max(cells) | Accumulator::Stride(cells) => cells.into_iter()
+                .map(Option::unwrap_or_default)
+                .collect(),
+        }
+    }
+}
+
+/// Reading downsampled regions of datasets, for any reader.
+#[async_trait(?Send)]
+pub trait N5AsyncDownsampleReader: N5AsyncReader {
+    /// Read a region of voxels, clamped to the dataset, reduced by integer
+    /// `factors` along each dimension. Cells at the region's upper edge may
+    /// be partial, and voxels of missing blocks are excluded, so cells of
+    /// only missing blocks are zero.
+    async fn read_region_downsampled<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        offset: &[u64],
+        size: &[u64],
+        factors: &[u64],
+        method: Downsampling,
+    ) -> Result<DownsampledRegion<T>, Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: Numeric,
+    {
+        if factors.len() != data_attrs.get_ndim() || factors.contains(&0) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Downsampling factors must be {} positive integers", data_attrs.get_ndim())));
+        }
+        let grid_positions = region_grid_positions(data_attrs, offset, size)?;
+
+        let clamped_size: Vec<u64> = offset.iter()
+            .zip(size)
+            .zip(data_attrs.get_dimensions())
+            .map(|((&o, &s), &dim)| s.min(dim.saturating_sub(o)))
+            .collect();
+        let downsampled_size: Vec<u64> = clamped_size.iter()
+            .zip(factors)
+            .map(|(&s, &f)| (s + f - 1) / f)
+            .collect();
+        let mut strides = Vec::with_capacity(downsampled_size.len());
+        let mut stride = 1;
+        for &dim in &downsampled_size {
+            strides.push(stride);
+            stride *= dim;
+        }
+
+        let mut accumulator = Accumulator::new(method, stride as usize);
+        let mut pending: FuturesUnordered<_> = grid_positions.into_iter()
+            .map(|grid_position| self.read_block::<T>(path_name, data_attrs, grid_position.into()))
+            .collect();
+        while let Some(block) = pending.try_next().await? {
+            if let Some(block) = block {
+                for_each_in_region(data_attrs, &block, offset, &clamped_size, |coord, &value| {
+                    let mut cell = 0;
+                    let mut first = true;
+                    for ((&c, &f), &stride) in coord.iter().zip(factors).zip(&strides) {
+                        cell += c / f * stride;
+                        first &= c % f == 0;
+                    }
+                    accumulator.add(cell as usize, first, value);
+                });
+            }
+        }
+
+        Ok(DownsampledRegion {
+            offset: offset.to_vec(),
+            size: downsampled_size,
+            factors: factors.to_vec(),
+            data: accumulator.finish(),
+        })
+    }
+}
+
+impl<R: N5AsyncReader> N5AsyncDownsampleReader for R {}
+
+
+pub trait N5PromiseDownsampleReader {
+    /// Resolve to the `DownsampledRegion` of a region of voxels reduced by
+    /// integer `factors`.
+    fn read_region_downsampled(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        offset: Coordinates,
+        size: Coordinates,
+        factors: Coordinates,
+        method: Downsampling,
+    ) -> PromiseDownsampledRegion;
+}
+
+impl<T> N5PromiseDownsampleReader for T where T: N5AsyncReader + Clone + 'static {
+    fn read_region_downsampled(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        offset: Coordinates,
+        size: Coordinates,
+        factors: Coordinates,
+        method: Downsampling,
+    ) -> PromiseDownsampledRegion {
+
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let data_attrs = data_attrs.0.clone();
+        let data_type = data_attrs.get_data_type().clone();
+        let offset = offset.to_vec();
+        let size = size.to_vec();
+        let factors = factors.to_vec();
+
+        data_type_match! {
+            data_type,
+            future_to_promise(map_future_error_wasm(async move {
+                let region = N5AsyncDownsampleReader::read_region_downsampled::<RsType>(
+                    &reader, &path_name, &data_attrs, &offset?, &size?, &factors?, method).await?;
+                Ok(JsValue::from(JsDownsampledRegion {
+                    data_type: data_attrs.get_data_type().clone(),
+                    offset: region.offset,
+                    size: region.size,
+                    factors: region.factors,
+                    data: <RsType as VecBlockMonomorphizerReflection>::data_to_js(&region.data),
+                }))
+            })).unchecked_into()
+        }
+    }
+}
+
+
+/// A downsampled region of a dataset, as exposed to JS.
+#[wasm_bindgen(js_name = DownsampledRegion)]
+pub struct JsDownsampledRegion {
+    data_type: DataType,
+    offset: Vec<u64>,
+    size: Vec<u64>,
+    factors: Vec<u64>,
+    data: JsValue,
+}
+
+#[wasm_bindgen(js_class = DownsampledRegion)]
+impl JsDownsampledRegion {
+    #[wasm_bindgen(getter)]
+    pub fn dtype(&self) -> DataTypeName {
+        JsValue::from(self.data_type.to_string()).unchecked_into()
+    }
+
+    /// Offset of the region, at full resolution.
+    #[wasm_bindgen(getter)]
+    pub fn offset(&self) -> Vec<u64> {
+        self.offset.clone()
+    }
+
+    /// Size of the downsampled data.
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> Vec<u64> {
+        self.size.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn factors(&self) -> Vec<u64> {
+        self.factors.clone()
+    }
+
+    /// The downsampled data in column-major order, in the typed array for
+    /// `dtype`.
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> BlockData {
+        self.data.clone().unchecked_into()
+    }
+}
@@ -0,0 +1,671 @@
+//! Access to local N5 containers without a web server, either through the
+//! File System Access API (`showDirectoryPicker`) or through directories
+//! dropped onto the page (`DataTransferItem.webkitGetAsEntry`).
+
+use std::str::FromStr;
+
+use js_sys::ArrayBuffer;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use super::*;
+use crate::object::{
+    N5AsyncObjectReader,
+    N5AsyncObjectWriter,
+    N5PromiseObjectReader,
+    N5PromiseObjectWriter,
+};
+
+
+// These APIs are not yet available in `web_sys`, so only the parts used here
+// are bound.
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends = js_sys::Object)]
+    #[derive(Clone, Debug)]
+    pub type FileSystemDirectoryHandle;
+
+    #[wasm_bindgen(method, js_name = getDirectoryHandle)]
+    fn get_directory_handle(
+        this: &FileSystemDirectoryHandle,
+        name: &str,
+        options: &JsValue,
+    ) -> Promise;
+
+    #[wasm_bindgen(method, js_name = getFileHandle)]
+    fn get_file_handle(
+        this: &FileSystemDirectoryHandle,
+        name: &str,
+        options: &JsValue,
+    ) -> Promise;
+
+    #[wasm_bindgen(method, js_name = removeEntry)]
+    fn remove_entry(
+        this: &FileSystemDirectoryHandle,
+        name: &str,
+        options: &JsValue,
+    ) -> Promise;
+
+    #[wasm_bindgen(method)]
+    fn entries(this: &FileSystemDirectoryHandle) -> js_sys::AsyncIterator;
+
+    #[wasm_bindgen(extends = js_sys::Object)]
+    type FileSystemFileHandle;
+
+    #[wasm_bindgen(method, js_name = getFile)]
+    fn get_file(this: &FileSystemFileHandle) -> Promise;
+
+    #[wasm_bindgen(method, js_name = createWritable)]
+    fn create_writable(this: &FileSystemFileHandle) -> Promise;
+
+    #[wasm_bindgen(extends = js_sys::Object)]
+    type FileSystemWritableFileStream;
+
+    #[wasm_bindgen(method)]
+    fn write(this: &FileSystemWritableFileStream, data: &JsValue) -> Promise;
+
+    #[wasm_bindgen(method)]
+    fn close(this: &FileSystemWritableFileStream) -> Promise;
+
+    #[wasm_bindgen(extends = js_sys::Object)]
+    #[derive(Clone, Debug)]
+    pub type FileSystemDirectoryEntry;
+
+    #[wasm_bindgen(method, js_name = getFile)]
+    fn get_file_entry(
+        this: &FileSystemDirectoryEntry,
+        path: &str,
+        options: &JsValue,
+        success: &js_sys::Function,
+        error: &js_sys::Function,
+    );
+
+    #[wasm_bindgen(method, js_name = getDirectory)]
+    fn get_directory_entry(
+        this: &FileSystemDirectoryEntry,
+        path: &str,
+        options: &JsValue,
+        success: &js_sys::Function,
+        error: &js_sys::Function,
+    );
+
+    #[wasm_bindgen(method, js_name = createReader)]
+    fn create_reader(this: &FileSystemDirectoryEntry) -> FileSystemDirectoryReader;
+
+    #[wasm_bindgen(extends = js_sys::Object)]
+    type FileSystemDirectoryReader;
+
+    #[wasm_bindgen(method, js_name = readEntries)]
+    fn read_entries(
+        this: &FileSystemDirectoryReader,
+        success: &js_sys::Function,
+        error: &js_sys::Function,
+    );
+
+    #[wasm_bindgen(extends = js_sys::Object)]
+    type FileSystemFileEntry;
+
+    #[wasm_bindgen(method)]
+    fn file(
+        this: &FileSystemFileEntry,
+        success: &js_sys::Function,
+        error: &js_sys::Function,
+    );
+
+    #[wasm_bindgen(extends = js_sys::Object)]
+    type Blob;
+
+    #[wasm_bindgen(method, js_name = arrayBuffer)]
+    fn array_buffer(this: &Blob) -> Promise;
+}
+
+/// Whether a rejection from either API means the entry does not exist (or is
+/// not of the requested kind), rather than some other failure.
+fn is_not_found(error: &JsValue) -> bool {
+    let name = js_sys::Reflect::get(error, &JsValue::from_str("name"))
+        .ok()
+        .and_then(|name| name.as_string());
+
+    match name.as_ref().map(String::as_str) {
+        Some("NotFoundError") | Some("TypeMismatchError") => true,
+        _ => false,
+    }
+}
+
+fn not_found_to_none<T>(result: Result<T, JsValue>) -> Result<Option<T>, JsValue> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(ref error) if is_not_found(error) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// Adapt a callback-style Entries API call to a future.
+async fn callback_future<F>(call: F) -> Result<JsValue, JsValue>
+        where F: FnOnce(&js_sys::Function, &js_sys::Function) {
+    let mut call = Some(call);
+    let promise = Promise::new(&mut |resolve, reject| {
+        if let Some(call) = call.take() {
+            call(&resolve, &reject);
+        }
+    });
+
+    JsFuture::from(promise).await
+}
+
+fn split_path(path_name: &str) -> Vec<&str> {
+    path_name.split('/').filter(|c| !c.is_empty()).collect()
+}
+
+fn create_options(create: bool) -> JsValue {
+    let options = js_sys::Object::new();
+    js_sys::Reflect::set(&options, &JsValue::from_str("create"), &JsValue::from(create))
+        .unwrap();
+    options.into()
+}
+
+#[derive(Clone)]
+enum DirectoryRoot {
+    Handle(FileSystemDirectoryHandle),
+    Entry(FileSystemDirectoryEntry),
+}
+
+impl DirectoryRoot {
+    async fn directory_handle(
+        handle: &FileSystemDirectoryHandle,
+        components: &[&str],
+        create: bool,
+    ) -> Result<FileSystemDirectoryHandle, JsValue> {
+        let mut dir = handle.clone();
+        for component in components {
+            dir = JsFuture::from(dir.get_directory_handle(component, &create_options(create)))
+                .await?
+                .unchecked_into();
+        }
+
+        Ok(dir)
+    }
+
+    /// Read a file, or `None` if it does not exist.
+    async fn read_file(&self, path_name: &str) -> Result<Option<Vec<u8>>, JsValue> {
+        let blob: Option<Blob> = match self {
+            DirectoryRoot::Handle(handle) => {
+                let components = split_path(path_name);
+                let (file_name, parents) = match components.split_last() {
+                    Some(split) => split,
+                    None => return Ok(None),
+                };
+                let file = not_found_to_none(async {
+                    let dir = Self::directory_handle(handle, parents, false).await?;
+                    let file_handle: FileSystemFileHandle = JsFuture::from(
+                            dir.get_file_handle(file_name, &create_options(false)))
+                        .await?
+                        .unchecked_into();
+                    JsFuture::from(file_handle.get_file()).await
+                }.await)?;
+                file.map(JsCast::unchecked_into)
+            },
+            DirectoryRoot::Entry(entry) => {
+                let file = not_found_to_none(async {
+                    let file_entry: FileSystemFileEntry = callback_future(|resolve, reject|
+                            entry.get_file_entry(path_name, &JsValue::UNDEFINED, resolve, reject))
+                        .await?
+                        .unchecked_into();
+                    callback_future(|resolve, reject| file_entry.file(resolve, reject)).await
+                }.await)?;
+                file.map(JsCast::unchecked_into)
+            },
+        };
+
+        match blob {
+            Some(blob) => {
+                let arrbuff_value = JsFuture::from(blob.array_buffer()).await?;
+                assert!(arrbuff_value.is_instance_of::<ArrayBuffer>());
+                Ok(Some(js_sys::Uint8Array::new(&arrbuff_value).to_vec()))
+            },
+            None => Ok(None),
+        }
+    }
+
+    async fn read_json(&self, path_name: &str) -> Result<Option<serde_json::Value>, Error> {
+        match self.read_file(path_name).await.map_err(convert_jsvalue_error)? {
+            Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn directory_exists(&self, path_name: &str) -> Result<bool, JsValue> {
+        let components = split_path(path_name);
+        match self {
+            DirectoryRoot::Handle(handle) => not_found_to_none(
+                    Self::directory_handle(handle, &components, false).await)
+                .map(|dir| dir.is_some()),
+            DirectoryRoot::Entry(entry) => {
+                if components.is_empty() {
+                    return Ok(true);
+                }
+                not_found_to_none(callback_future(|resolve, reject|
+                        entry.get_directory_entry(path_name, &JsValue::UNDEFINED, resolve, reject))
+                    .await)
+                .map(|dir| dir.is_some())
+            },
+        }
+    }
+
+    /// Names of the child directories of a directory.
+    async fn list_directories(&self, path_name: &str) -> Result<Vec<String>, JsValue> {
+        let components = split_path(path_name);
+        let mut children = Vec::new();
+        match self {
+            DirectoryRoot::Handle(handle) => {
+                let dir = Self::directory_handle(handle, &components, false).await?;
+                let entries = dir.entries();
+                loop {
+                    let next = JsFuture::from(entries.next()?).await?;
+                    let next: js_sys::IteratorNext = next.unchecked_into();
+                    if next.done() {
+                        break;
+                    }
+                    let pair: js_sys::Array = next.value().unchecked_into();
+                    let kind = js_sys::Reflect::get(&pair.get(1), &JsValue::from_str("kind"))?;
+                    if kind.as_string().as_ref().map(String::as_str) == Some("directory") {
+                        children.push(pair.get(0).as_string().unwrap());
+                    }
+                }
+            },
+            DirectoryRoot::Entry(entry) => {
+                let dir: FileSystemDirectoryEntry = if components.is_empty() {
+                    entry.clone()
+                } else {
+                    callback_future(|resolve, reject|
+                            entry.get_directory_entry(path_name, &JsValue::UNDEFINED, resolve, reject))
+                        .await?
+                        .unchecked_into()
+                };
+                let reader = dir.create_reader();
+                // `readEntries` returns results in batches until it returns
+                // an empty batch.
+                loop {
+                    let batch: js_sys::Array = callback_future(|resolve, reject|
+                            reader.read_entries(resolve, reject))
+                        .await?
+                        .unchecked_into();
+                    if batch.length() == 0 {
+                        break;
+                    }
+                    for child in batch.iter() {
+                        let is_dir = js_sys::Reflect::get(&child, &JsValue::from_str("isDirectory"))?;
+                        if is_dir.as_bool().unwrap_or(false) {
+                            let name = js_sys::Reflect::get(&child, &JsValue::from_str("name"))?;
+                            children.push(name.as_string().unwrap());
+                        }
+                    }
+                }
+            },
+        }
+
+        Ok(children)
+    }
+
+    fn writable_handle(&self) -> Result<&FileSystemDirectoryHandle, Error> {
+        match self {
+            DirectoryRoot::Handle(handle) => Ok(handle),
+            DirectoryRoot::Entry(_) => Err(Error::new(
+                ErrorKind::PermissionDenied,
+                "Dropped directories are read-only")),
+        }
+    }
+
+    async fn write_file(&self, path_name: &str, bytes: &[u8]) -> Result<(), Error> {
+        let handle = self.writable_handle()?;
+        let components = split_path(path_name);
+        let (file_name, parents) = components.split_last()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Empty file path"))?;
+
+        let to_return = async {
+            let dir = Self::directory_handle(handle, parents, true).await?;
+            let file_handle: FileSystemFileHandle = JsFuture::from(
+                    dir.get_file_handle(file_name, &create_options(true)))
+                .await?
+                .unchecked_into();
+            let writable: FileSystemWritableFileStream = JsFuture::from(file_handle.create_writable())
+                .await?
+                .unchecked_into();
+            JsFuture::from(writable.write(&js_sys::Uint8Array::from(bytes).into())).await?;
+            JsFuture::from(writable.close()).await?;
+
+            Ok::<_, JsValue>(())
+        };
+
+        map_future_error_rust(to_return).await
+    }
+
+    async fn create_directory(&self, path_name: &str) -> Result<(), Error> {
+        let handle = self.writable_handle()?;
+        let components = split_path(path_name);
+        map_future_error_rust(Self::directory_handle(handle, &components, true)).await
+            .map(|_| ())
+    }
+
+    /// Remove a file or directory (recursively), returning whether it existed.
+    async fn remove_entry(&self, path_name: &str) -> Result<bool, Error> {
+        let handle = self.writable_handle()?;
+        let components = split_path(path_name);
+        let (name, parents) = match components.split_last() {
+            Some(split) => split,
+            None => return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Can not remove the container root")),
+        };
+
+        let to_return = not_found_to_none(async {
+            let dir = Self::directory_handle(handle, parents, false).await?;
+            let options = js_sys::Object::new();
+            js_sys::Reflect::set(&options, &JsValue::from_str("recursive"), &JsValue::TRUE)?;
+            JsFuture::from(dir.remove_entry(name, &options)).await
+        }.await);
+
+        to_return
+            .map(|removed| removed.is_some())
+            .map_err(convert_jsvalue_error)
+    }
+}
+
+
+/// N5 container in a local directory.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct N5FileSystem {
+    root: DirectoryRoot,
+}
+
+impl N5FileSystem {
+    async fn get_attributes(&self, path_name: &str) -> Result<serde_json::Value, Error> {
+        let path = get_dataset_attributes_path(path_name);
+        Ok(self.root.read_json(&path).await?
+            .unwrap_or_else(|| serde_json::Value::Object(serde_json::Map::new())))
+    }
+
+    fn open_root(root: DirectoryRoot) -> PromiseN5FileSystem {
+        let reader = N5FileSystem { root };
+
+        let to_return = async move {
+            let version = N5AsyncReader::get_version(&reader).await?;
+
+            if !n5::is_version_compatible(&n5::VERSION, &version) {
+                return Err(Error::new(ErrorKind::Other, "TODO: Incompatible version"))
+            }
+
+            Ok(JsValue::from(reader))
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
+}
+
+#[wasm_bindgen]
+impl N5FileSystem {
+    /// Open a container from a directory handle, such as one returned by
+    /// `showDirectoryPicker`. Writes require the handle to have been granted
+    /// `readwrite` permission.
+    pub fn open_directory_handle(handle: FileSystemDirectoryHandle) -> PromiseN5FileSystem {
+        Self::open_root(DirectoryRoot::Handle(handle))
+    }
+
+    /// Create a new container in an empty directory handle, or open an
+    /// existing one.
+    pub fn create_directory_handle(handle: FileSystemDirectoryHandle) -> PromiseN5FileSystem {
+        let writer = N5FileSystem { root: DirectoryRoot::Handle(handle) };
+
+        let to_return = async move {
+            let mut version = serde_json::Map::new();
+            version.insert(
+                n5::VERSION_ATTRIBUTE_KEY.to_owned(),
+                serde_json::Value::String(n5::VERSION.to_string()));
+            N5AsyncWriter::set_attributes(&writer, "", version).await?;
+
+            Ok(JsValue::from(writer))
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
+
+    /// Open a read-only container from a dropped directory, as returned by
+    /// `DataTransferItem.webkitGetAsEntry`.
+    pub fn open_entry(entry: FileSystemDirectoryEntry) -> PromiseN5FileSystem {
+        Self::open_root(DirectoryRoot::Entry(entry))
+    }
+}
+
+/// Delegations to expose N5PromiseReader, N5PromiseWriter and object dataset
+/// traits to WASM.
+#[wasm_bindgen]
+impl N5FileSystem {
+    pub fn get_version(&self) -> PromiseVersion {
+        N5PromiseReader::get_version(self)
+    }
+
+    pub fn get_dataset_attributes(&self, path_name: &str) -> PromiseDatasetAttributes {
+        N5PromiseReader::get_dataset_attributes(self, path_name)
+    }
+
+    pub fn exists(&self, path_name: &str) -> PromiseBool {
+        N5PromiseReader::exists(self, path_name)
+    }
+
+    pub fn dataset_exists(&self, path_name: &str) -> PromiseBool {
+        N5PromiseReader::dataset_exists(self, path_name)
+    }
+
+    pub fn read_block(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        grid_position: Coordinates,
+    ) -> PromiseDataBlock {
+        N5PromiseReader::read_block(self, path_name, data_attrs, grid_position)
+    }
+
+    pub fn iter_blocks(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        offset: Coordinates,
+        size: Coordinates,
+    ) -> Result<DataBlockAsyncIterator, JsValue> {
+        N5PromiseReader::iter_blocks(self, path_name, data_attrs, offset, size)
+    }
+
+    pub fn list(&self, path_name: &str) -> PromiseStrings {
+        N5PromiseReader::list(self, path_name)
+    }
+
+    pub fn list_attributes(&self, path_name: &str) -> PromiseAttributes {
+        N5PromiseReader::list_attributes(self, path_name)
+    }
+
+    pub fn set_attributes(&self, path_name: &str, attributes: AttributesJson) -> PromiseVoid {
+        N5PromiseWriter::set_attributes(self, path_name, attributes)
+    }
+
+    pub fn create_group(&self, path_name: &str) -> PromiseVoid {
+        N5PromiseWriter::create_group(self, path_name)
+    }
+
+    pub fn create_dataset(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+    ) -> PromiseVoid {
+        N5PromiseWriter::create_dataset(self, path_name, data_attrs)
+    }
+
+    pub fn remove(&self, path_name: &str) -> PromiseVoid {
+        N5PromiseWriter::remove(self, path_name)
+    }
+
+    pub fn write_block(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        grid_position: Coordinates,
+        data: BlockData,
+    ) -> PromiseVoid {
+        N5PromiseWriter::write_block(self, path_name, data_attrs, grid_position, data)
+    }
+
+    pub fn delete_block(&self, path_name: &str, grid_position: Coordinates) -> PromiseBool {
+        N5PromiseWriter::delete_block(self, path_name, grid_position)
+    }
+
+    pub fn get_object_dataset_attributes(&self, path_name: &str) -> PromiseObjectDatasetAttributes {
+        N5PromiseObjectReader::get_object_dataset_attributes(self, path_name)
+    }
+
+    pub fn read_object_block(&self, path_name: &str, grid_position: Coordinates) -> PromiseBytes {
+        N5PromiseObjectReader::read_object_block(self, path_name, grid_position)
+    }
+
+    pub fn create_object_dataset(&self, path_name: &str, data_attrs: AttributesJson) -> PromiseVoid {
+        N5PromiseObjectWriter::create_object_dataset(self, path_name, data_attrs)
+    }
+
+    pub fn write_object_block(
+        &self,
+        path_name: &str,
+        grid_position: Coordinates,
+        data: Vec<u8>,
+    ) -> PromiseVoid {
+        N5PromiseObjectWriter::write_object_block(self, path_name, grid_position, data)
+    }
+}
+
+region_reader_delegations!(N5FileSystem);
+
+#[async_trait(?Send)]
+impl N5AsyncReader for N5FileSystem {
+    async fn get_version(&self) -> Result<n5::Version, Error> {
+        let attr = self.get_attributes("").await?;
+        let ver = attr.get(n5::VERSION_ATTRIBUTE_KEY)
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "Not an N5 root"))?;
+
+        n5::Version::from_str(ver.as_str().unwrap_or(""))
+            .map_err(|error| Error::new(ErrorKind::InvalidData, format!("Invalid N5 version: {}", error)))
+    }
+
+    async fn get_dataset_attributes(&self, path_name: &str) -> Result<n5::DatasetAttributes, Error> {
+        let path = get_dataset_attributes_path(path_name);
+        let json = self.root.read_json(&path).await?
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "Dataset attributes not found"))?;
+
+        Ok(serde_json::from_value(json)?)
+    }
+
+    async fn exists(&self, path_name: &str) -> Result<bool, Error> {
+        map_future_error_rust(self.root.directory_exists(path_name)).await
+    }
+
+    async fn read_block_with_mode<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<Option<(VecDataBlock<T>, BlockMode)>, Error>
+        where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+            T: ReflectedType,
+    {
+        let block_path = relative_block_path(path_name, &grid_position);
+
+        match map_future_error_rust(self.root.read_file(&block_path)).await? {
+            Some(buff) => Ok(Some(decode_block(&buff, data_attrs, grid_position)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn list(&self, path_name: &str) -> Result<Vec<String>, Error> {
+        map_future_error_rust(self.root.list_directories(path_name)).await
+    }
+
+    async fn list_attributes(&self, path_name: &str) -> Result<serde_json::Value, Error> {
+        self.get_attributes(path_name).await
+    }
+}
+
+#[async_trait(?Send)]
+impl N5AsyncWriter for N5FileSystem {
+    async fn set_attributes(
+        &self,
+        path_name: &str,
+        attributes: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<(), Error> {
+        let path = get_dataset_attributes_path(path_name);
+        let mut existing = match self.root.read_json(&path).await? {
+            Some(serde_json::Value::Object(map)) => map,
+            _ => serde_json::Map::new(),
+        };
+        merge_attributes(&mut existing, attributes);
+
+        let bytes = serde_json::to_vec(&existing)?;
+        self.root.write_file(&path, &bytes).await
+    }
+
+    async fn create_group(&self, path_name: &str) -> Result<(), Error> {
+        self.root.create_directory(path_name).await
+    }
+
+    async fn remove(&self, path_name: &str) -> Result<(), Error> {
+        self.root.remove_entry(path_name).await.map(|_| ())
+    }
+
+    async fn write_block<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        block: &VecDataBlock<T>,
+    ) -> Result<(), Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::WriteableDataBlock,
+                T: ReflectedType,
+    {
+        let block_path = relative_block_path(path_name, block.get_grid_position());
+        let bytes = encode_block(data_attrs, block)?;
+
+        self.root.write_file(&block_path, &bytes).await
+    }
+
+    async fn delete_block(
+        &self,
+        path_name: &str,
+        grid_position: &[u64],
+    ) -> Result<bool, Error> {
+        let block_path = relative_block_path(path_name, grid_position);
+
+        self.root.remove_entry(&block_path).await
+    }
+}
+
+#[async_trait(?Send)]
+impl N5AsyncObjectReader for N5FileSystem {
+    async fn read_block_bytes(
+        &self,
+        path_name: &str,
+        grid_position: &[u64],
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let block_path = relative_block_path(path_name, grid_position);
+
+        map_future_error_rust(self.root.read_file(&block_path)).await
+    }
+}
+
+#[async_trait(?Send)]
+impl N5AsyncObjectWriter for N5FileSystem {
+    async fn write_block_bytes(
+        &self,
+        path_name: &str,
+        grid_position: &[u64],
+        bytes: Vec<u8>,
+    ) -> Result<(), Error> {
+        let block_path = relative_block_path(path_name, grid_position);
+
+        self.root.write_file(&block_path, &bytes).await
+    }
+}
@@ -1,42 +1,120 @@
-use std::fmt::Write;
+use std::cell::Cell;
+use std::rc::Rc;
 use std::str::FromStr;
 
 use js_sys::ArrayBuffer;
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::JsFuture;
-use web_sys::{
-    Request,
-    RequestInit,
-    RequestMode,
-    Response,
-};
+use web_sys::Response;
 
 use super::*;
+use crate::object::{
+    N5AsyncObjectReader,
+    N5PromiseObjectReader,
+};
+use crate::transport::{
+    GlobalFetch,
+    JsCallbackTransport,
+    Transport,
+};
+
 
+const GCS_HOST: &str = "storage.googleapis.com";
+const AZURE_HOST_SUFFIX: &str = ".blob.core.windows.net";
+/// Azure requires an explicit API version for bearer token authorization.
+const AZURE_API_VERSION: &str = "2020-04-08";
 
-const ATTRIBUTES_FILE: &str = "attributes.json";
 
-enum GlobalProxy {
-    Window(web_sys::Window),
-    WorkerGlobalScope(web_sys::WorkerGlobalScope),
+/// Object stores recognized from a container location, whose bucket listing
+/// APIs can be used to `list` groups.
+#[derive(Clone, Debug, PartialEq)]
+enum ObjectStore {
+    Plain,
+    /// Google Cloud Storage, listed with the JSON API.
+    Gcs { bucket: String, prefix: String },
+    /// Azure Blob Storage, listed with the List Blobs API.
+    Azure { container_url: String, prefix: String },
 }
 
-impl GlobalProxy {
-    fn fetch_with_request(&self, request: &Request) -> Promise {
-        match self {
-            GlobalProxy::Window(window) => window.fetch_with_request(request),
-            GlobalProxy::WorkerGlobalScope(scope) => scope.fetch_with_request(request),
+/// A container location resolved to an HTTP base path, a query string (such
+/// as an Azure SAS token) to append to every request, and its object store.
+#[derive(Clone, Debug, PartialEq)]
+struct Location {
+    base_path: String,
+    query: String,
+    store: ObjectStore,
+}
+
+impl Location {
+    /// Resolve `gs://bucket/path`, `https://storage.googleapis.com/bucket/path`
+    /// and `https://account.blob.core.windows.net/container/path?sas`
+    /// locations. Anything else is used as a plain HTTP base path.
+    fn resolve(location: &str) -> Location {
+        let (location, query) = match location.find('?') {
+            Some(index) => (&location[..index], &location[index..]),
+            None => (location, ""),
+        };
+        let location = location.trim_end_matches('/');
+
+        if let Some(path) = location.strip_prefix("gs://") {
+            return Location::gcs(path, query);
+        }
+
+        let authority_and_path = location.strip_prefix("https://")
+            .or_else(|| location.strip_prefix("http://"));
+        if let Some(authority_and_path) = authority_and_path {
+            let mut parts = authority_and_path.splitn(2, '/');
+            let host = parts.next().unwrap_or("");
+            let path = parts.next().unwrap_or("");
+            let mut path_parts = path.splitn(2, '/');
+            let bucket = path_parts.next().unwrap_or("");
+            let prefix = path_parts.next().unwrap_or("");
+
+            if host == GCS_HOST && !bucket.is_empty() {
+                return Location::gcs(path, query);
+            }
+            if host.ends_with(AZURE_HOST_SUFFIX) && !bucket.is_empty() {
+                return Location {
+                    base_path: location.to_owned(),
+                    query: query.to_owned(),
+                    store: ObjectStore::Azure {
+                        container_url: format!("https://{}/{}", host, bucket),
+                        prefix: prefix.to_owned(),
+                    },
+                };
+            }
+        }
+
+        Location {
+            base_path: location.to_owned(),
+            query: query.to_owned(),
+            store: ObjectStore::Plain,
         }
     }
-}
 
-fn self_() -> Result<GlobalProxy, JsValue> {
-    let global = js_sys::global();
-    if js_sys::eval("typeof WorkerGlobalScope !== 'undefined'")?.as_bool().unwrap() {
-        Ok(global.dyn_into::<web_sys::WorkerGlobalScope>().map(GlobalProxy::WorkerGlobalScope)?)
+    /// URL of a path within the container. Object store keys are encoded,
+    /// while plain HTTP paths are used verbatim, as they may already be
+    /// encoded.
+    fn url(&self, path_name: &str) -> String {
+        match self.store {
+            ObjectStore::Plain => format!("{}/{}{}", &self.base_path, path_name, &self.query),
+            _ => format!("{}/{}{}", &self.base_path, uri_encode(path_name, false), &self.query),
+        }
     }
-    else {
-        Ok(global.dyn_into::<web_sys::Window>().map(GlobalProxy::Window)?)
+
+    fn gcs(path: &str, query: &str) -> Location {
+        let mut parts = path.splitn(2, '/');
+        let bucket = parts.next().unwrap_or("");
+        let prefix = parts.next().unwrap_or("");
+
+        Location {
+            base_path: format!("https://{}/{}", GCS_HOST, path),
+            query: query.to_owned(),
+            store: ObjectStore::Gcs {
+                bucket: bucket.to_owned(),
+                prefix: prefix.to_owned(),
+            },
+        }
     }
 }
 
@@ -44,98 +122,354 @@ fn self_() -> Result<GlobalProxy, JsValue> {
 #[wasm_bindgen]
 #[derive(Clone)]
 pub struct N5HTTPFetch {
-    base_path: String,
+    /// Locations serving the same container. The first is the primary,
+    /// which is used for listing and is tried first for metadata.
+    mirrors: Rc<Vec<Location>>,
+    /// Mirror to try first for the next block read, shared between clones.
+    next_mirror: Rc<Cell<usize>>,
+    headers: Vec<(String, String)>,
+    transport: Rc<dyn Transport>,
 }
 
 impl N5HTTPFetch {
-    fn fetch(&self, path_name: &str) -> JsFuture {
-        let mut request_options = RequestInit::new();
-        request_options.method("GET");
-        request_options.mode(RequestMode::Cors);
+    /// Whether a failed response means that a path is missing. Public GCS
+    /// buckets and Azure containers without list permission answer 403 rather
+    /// than 404 for missing objects, so there both mean missing.
+    fn is_missing(&self, resp: &Response) -> bool {
+        match resp.status() {
+            404 => true,
+            403 => self.primary().store != ObjectStore::Plain,
+            _ => false,
+        }
+    }
 
-        let req = Request::new_with_str_and_init(
-            &format!("{}/{}", &self.base_path, path_name),
-            &request_options).unwrap();
+    fn primary(&self) -> &Location {
+        &self.mirrors[0]
+    }
 
-        let req_promise = self_().unwrap().fetch_with_request(&req);
+    /// Take the next mirror in round-robin order.
+    fn next_mirror(&self) -> usize {
+        let mirror = self.next_mirror.get();
+        self.next_mirror.set((mirror + 1) % self.mirrors.len());
 
-        JsFuture::from(req_promise)
+        mirror
     }
 
-    fn fetch_json(&self, path_name: &str) -> impl Future<Item = JsValue, Error = JsValue> {
-        self.fetch(path_name).and_then(|resp_value| {
-            assert!(resp_value.is_instance_of::<Response>());
-            let resp: Response = resp_value.dyn_into()?;
+    async fn request(&self, path_name: &str, method: &str) -> Result<Response, JsValue> {
+        self.request_from(0, path_name, method).await
+    }
 
-            resp.json()
-        }).and_then(|json_value: Promise| {
-            JsFuture::from(json_value)
-        })
+    /// Make a request to each mirror in turn, starting from `first`, until
+    /// one responds without a network error or server error. If all fail,
+    /// the last failure is returned as an error.
+    async fn request_from(
+        &self,
+        first: usize,
+        path_name: &str,
+        method: &str,
+    ) -> Result<Response, JsValue> {
+        let mut last = None;
+        for i in 0..self.mirrors.len() {
+            let mirror = &self.mirrors[(first + i) % self.mirrors.len()];
+            let result = self.request_url(&mirror.url(path_name), method).await;
+            match result {
+                Ok(ref resp) if resp.status() < 500 => return result,
+                _ => last = Some(result),
+            }
+        }
+
+        match last.expect("Containers have at least one location") {
+            Ok(resp) => Err(js_sys::Error::new(&format!(
+                "Request for {} failed on all mirrors with status {}",
+                path_name, resp.status())).into()),
+            Err(error) => Err(error),
+        }
     }
 
-    fn get_attributes(&self, path_name: &str) -> impl Future<Item = serde_json::Value, Error = Error> {
-        let path = self.get_dataset_attributes_path(path_name);
-        let to_return = self
-            .fetch_json(&path)
-            .map(|json| json.into_serde().unwrap());
+    async fn request_url(&self, url: &str, method: &str) -> Result<Response, JsValue> {
+        let headers: Vec<(&str, &str)> = self.headers.iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
 
-        map_future_error_rust(to_return)
+        self.transport.fetch(url, method, &headers, None).await
     }
 
-    fn relative_block_path(&self, path_name: &str, grid_position: &[u64]) -> String {
-        let mut block_path = path_name.to_owned();
-        for coord in grid_position {
-            write!(block_path, "/{}", coord).unwrap();
+    async fn fetch(&self, path_name: &str) -> Result<Response, JsValue> {
+        self.request(path_name, "GET").await
+    }
+
+    async fn fetch_json(&self, path_name: &str) -> Result<JsValue, JsValue> {
+        let resp = self.fetch(path_name).await?;
+
+        JsFuture::from(resp.json()?).await
+    }
+
+    async fn get_attributes(&self, path_name: &str) -> Result<serde_json::Value, Error> {
+        let path = get_dataset_attributes_path(path_name);
+        let resp = map_future_error_rust(self.fetch(&path)).await?;
+        if self.is_missing(&resp) {
+            return Err(Error::new(ErrorKind::NotFound, format!("No attributes at {}", path)));
         }
+        let json = map_future_error_rust(async { JsFuture::from(resp.json()?).await }).await?;
 
-        block_path
+        Ok(json.into_serde().unwrap())
     }
 
-    fn get_dataset_attributes_path(&self, path_name: &str) -> String {
-        if path_name.is_empty() {
-            ATTRIBUTES_FILE.to_owned()
-        } else {
-            format!("{}/{}", path_name, ATTRIBUTES_FILE)
+    /// Open a container from Rust, checking that its N5 version is compatible.
+    pub async fn open_reader(base_path: &str) -> Result<N5HTTPFetch, Error> {
+        Self::open_reader_with_transport(base_path, Rc::new(GlobalFetch)).await
+    }
+
+    /// Open a container from Rust, making all requests through `transport`.
+    pub async fn open_reader_with_transport(
+        base_path: &str,
+        transport: Rc<dyn Transport>,
+    ) -> Result<N5HTTPFetch, Error> {
+        Self::open_reader_with_options(base_path, None, transport).await
+    }
+
+    /// Open a container from Rust, authorizing requests with an OAuth bearer
+    /// token if one is given.
+    pub async fn open_reader_with_options(
+        base_path: &str,
+        bearer_token: Option<&str>,
+        transport: Rc<dyn Transport>,
+    ) -> Result<N5HTTPFetch, Error> {
+        Self::open_reader_with_mirrors(&[base_path], bearer_token, transport).await
+    }
+
+    /// Open a container served from several locations, the first of which is
+    /// the primary. Block reads are spread across the mirrors, and all
+    /// requests fail over to the next mirror on network or server errors.
+    /// The root attributes of all mirrors must be identical. This is only
+    /// checked when opening, and only for the root attributes, so mirrors
+    /// whose datasets differ are not detected. Reads with etags always start
+    /// from the primary.
+    pub async fn open_reader_with_mirrors(
+        base_paths: &[&str],
+        bearer_token: Option<&str>,
+        transport: Rc<dyn Transport>,
+    ) -> Result<N5HTTPFetch, Error> {
+        if base_paths.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidInput, "No container locations given"));
+        }
+        let mirrors: Vec<Location> = base_paths.iter()
+            .map(|base_path| Location::resolve(base_path))
+            .collect();
+
+        let mut headers = Vec::new();
+        if let Some(token) = bearer_token {
+            headers.push(("Authorization".to_owned(), format!("Bearer {}", token)));
+            if mirrors.iter().any(|mirror| matches!(mirror.store, ObjectStore::Azure { .. })) {
+                headers.push(("x-ms-version".to_owned(), AZURE_API_VERSION.to_owned()));
+            }
+        }
+
+        let reader = N5HTTPFetch {
+            mirrors: Rc::new(mirrors),
+            next_mirror: Rc::new(Cell::new(0)),
+            headers,
+            transport,
+        };
+
+        if reader.mirrors.len() > 1 {
+            let root_attributes = futures::future::try_join_all(
+                (0..reader.mirrors.len()).map(|mirror| reader.mirror_root_attributes(mirror))).await?;
+            if root_attributes.iter().any(|attributes| attributes != &root_attributes[0]) {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "Mirrors do not have matching root attributes"));
+            }
+        }
+
+        let version = N5AsyncReader::get_version(&reader).await?;
+
+        if !n5::is_version_compatible(&n5::VERSION, &version) {
+            return Err(Error::new(ErrorKind::Other, "TODO: Incompatible version"))
+        }
+
+        Ok(reader)
+    }
+
+    /// Root attributes of one mirror, without failover.
+    async fn mirror_root_attributes(&self, mirror: usize) -> Result<serde_json::Value, Error> {
+        let location = &self.mirrors[mirror];
+        let resp = map_future_error_rust(
+            self.request_url(&location.url(ATTRIBUTES_FILE), "GET")).await?;
+        if !resp.ok() {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!("Mirror {} is not an N5 root", location.base_path)));
+        }
+        let json = map_future_error_rust(
+            JsFuture::from(resp.json().map_err(convert_jsvalue_error)?)).await?;
+
+        Ok(json.into_serde()?)
+    }
+
+    /// List the names of child prefixes through the primary location's
+    /// object store listing API, following pagination.
+    async fn list_store(&self, path_name: &str) -> Result<Vec<String>, JsValue> {
+        let primary = self.primary();
+        let (store_prefix, is_gcs) = match &primary.store {
+            ObjectStore::Plain => return Err(js_sys::Error::new(
+                "Listing is only supported for containers in GCS or Azure").into()),
+            ObjectStore::Gcs { prefix, .. } => (prefix, true),
+            ObjectStore::Azure { prefix, .. } => (prefix, false),
+        };
+        let prefix: String = [store_prefix.as_str(), path_name.trim_matches('/')].iter()
+            .filter(|part| !part.is_empty())
+            .map(|part| format!("{}/", part))
+            .collect();
+
+        let mut children = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let url = match &primary.store {
+                ObjectStore::Gcs { bucket, .. } => {
+                    let mut url = format!(
+                        "https://{}/storage/v1/b/{}/o?prefix={}&delimiter=%2F&fields=prefixes%2CnextPageToken",
+                        GCS_HOST,
+                        uri_encode(bucket, true),
+                        uri_encode(&prefix, true));
+                    if let Some(token) = &page_token {
+                        write!(url, "&pageToken={}", uri_encode(token, true)).unwrap();
+                    }
+                    url
+                },
+                ObjectStore::Azure { container_url, .. } => {
+                    let mut url = format!(
+                        "{}?restype=container&comp=list&prefix={}&delimiter=%2F",
+                        container_url,
+                        uri_encode(&prefix, true));
+                    if let Some(marker) = &page_token {
+                        write!(url, "&marker={}", uri_encode(marker, true)).unwrap();
+                    }
+                    // Append the SAS token, if any, without its leading `?`.
+                    if !primary.query.is_empty() {
+                        write!(url, "&{}", &primary.query[1..]).unwrap();
+                    }
+                    url
+                },
+                ObjectStore::Plain => unreachable!(),
+            };
+
+            let resp = self.request_url(&url, "GET").await?;
+            if !resp.ok() {
+                return Err(js_sys::Error::new(
+                    &format!("Listing failed with status {}", resp.status())).into());
+            }
+
+            let child_prefixes: Vec<String>;
+            if is_gcs {
+                let page: serde_json::Value = JsFuture::from(resp.json()?).await?
+                    .into_serde()
+                    .map_err(|e| js_sys::Error::new(&e.to_string()))?;
+                child_prefixes = page["prefixes"].as_array()
+                    .map(|prefixes| prefixes.iter()
+                        .filter_map(|p| p.as_str().map(ToOwned::to_owned))
+                        .collect())
+                    .unwrap_or_default();
+                page_token = page["nextPageToken"].as_str().map(ToOwned::to_owned);
+            } else {
+                let xml = JsFuture::from(resp.text()?).await?.as_string().unwrap_or_default();
+                child_prefixes = xml_elements(&xml, "BlobPrefix").iter()
+                    .filter_map(|blob_prefix| xml_elements(blob_prefix, "Name").first()
+                        .map(|name| xml_unescape(name)))
+                    .collect();
+                page_token = xml_elements(&xml, "NextMarker").first()
+                    .filter(|marker| !marker.is_empty())
+                    .map(|marker| xml_unescape(marker));
+            }
+
+            children.extend(child_prefixes.iter()
+                .filter_map(|child| child.get(prefix.len()..))
+                .map(|name| name.trim_end_matches('/').to_owned())
+                .filter(|name| !name.is_empty()));
+
+            if page_token.is_none() {
+                break;
+            }
         }
+        children.sort();
+
+        Ok(children)
     }
 }
 
 #[wasm_bindgen]
 impl N5HTTPFetch {
-    pub fn open(base_path: &str) -> Promise {
-        let reader = N5HTTPFetch {
-            base_path: base_path.into(),
+    pub fn open(base_path: &str) -> PromiseN5HTTPFetch {
+        let base_path = base_path.to_owned();
+        let to_return = async move {
+            N5HTTPFetch::open_reader(&base_path).await
+                .map(JsValue::from)
         };
 
-        let to_return = N5AsyncReader::get_version(&reader).and_then(|version| {
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
 
-            if !n5::is_version_compatible(&n5::VERSION, &version) {
-                return future::err(Error::new(ErrorKind::Other, "TODO: Incompatible version"))
-            }
+    /// Open a container, making all requests through a JS callback called as
+    /// `transport(url, method, headers, body)` and returning a promise of a
+    /// `Response` or response-like object.
+    pub fn open_with_transport(base_path: &str, transport: js_sys::Function) -> PromiseN5HTTPFetch {
+        let base_path = base_path.to_owned();
+        let transport = Rc::new(JsCallbackTransport::new(transport));
+        let to_return = async move {
+            N5HTTPFetch::open_reader_with_transport(&base_path, transport).await
+                .map(JsValue::from)
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
+
+    /// Open a container, authorizing requests with an OAuth bearer token,
+    /// such as a Google Cloud or Azure AD access token.
+    pub fn open_with_token(base_path: &str, bearer_token: &str) -> PromiseN5HTTPFetch {
+        let base_path = base_path.to_owned();
+        let bearer_token = bearer_token.to_owned();
+        let to_return = async move {
+            N5HTTPFetch::open_reader_with_options(&base_path, Some(&bearer_token), Rc::new(GlobalFetch)).await
+                .map(JsValue::from)
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
 
-            future::ok(JsValue::from(reader))
-        });
+    /// Open a container served from an array of locations, the first of
+    /// which is the primary.
+    pub fn open_mirrors(base_paths: js_sys::Array) -> PromiseN5HTTPFetch {
+        let to_return = async move {
+            let base_paths = base_paths.iter()
+                .map(|base_path| base_path.as_string())
+                .collect::<Option<Vec<String>>>()
+                .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Locations must be strings"))?;
+            let base_paths: Vec<&str> = base_paths.iter().map(String::as_str).collect();
+            N5HTTPFetch::open_reader_with_mirrors(&base_paths, None, Rc::new(GlobalFetch)).await
+                .map(JsValue::from)
+        };
 
-        future_to_promise(map_future_error_wasm(to_return))
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
     }
 }
 
-/// Delegations to expose N5PromiseReader trait to WASM.
+/// Delegations to expose N5PromiseReader, N5PromiseEtagReader and
+/// N5PromiseObjectReader traits to WASM.
 #[wasm_bindgen]
 impl N5HTTPFetch {
-    pub fn get_version(&self) -> Promise {
+    pub fn get_version(&self) -> PromiseVersion {
         N5PromiseReader::get_version(self)
     }
 
-    pub fn get_dataset_attributes(&self, path_name: &str) -> Promise {
+    pub fn get_dataset_attributes(&self, path_name: &str) -> PromiseDatasetAttributes {
         N5PromiseReader::get_dataset_attributes(self, path_name)
     }
 
-    pub fn exists(&self, path_name: &str) -> Promise {
+    pub fn exists(&self, path_name: &str) -> PromiseBool {
         N5PromiseReader::exists(self, path_name)
     }
 
-    pub fn dataset_exists(&self, path_name: &str) -> Promise {
+    pub fn dataset_exists(&self, path_name: &str) -> PromiseBool {
         N5PromiseReader::dataset_exists(self, path_name)
     }
 
@@ -143,12 +477,28 @@ impl N5HTTPFetch {
         &self,
         path_name: &str,
         data_attrs: &wrapped::DatasetAttributes,
-        grid_position: Vec<u64>,
-    ) -> Promise {
+        grid_position: Coordinates,
+    ) -> PromiseDataBlock {
         N5PromiseReader::read_block(self, path_name, data_attrs, grid_position)
     }
 
-    pub fn list_attributes(&self, path_name: &str) -> Promise {
+    pub fn iter_blocks(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        offset: Coordinates,
+        size: Coordinates,
+    ) -> Result<DataBlockAsyncIterator, JsValue> {
+        N5PromiseReader::iter_blocks(self, path_name, data_attrs, offset, size)
+    }
+
+    /// List child groups and datasets. Only supported for containers in GCS
+    /// or Azure.
+    pub fn list(&self, path_name: &str) -> PromiseStrings {
+        N5PromiseReader::list(self, path_name)
+    }
+
+    pub fn list_attributes(&self, path_name: &str) -> PromiseAttributes {
         N5PromiseReader::list_attributes(self, path_name)
     }
 
@@ -156,8 +506,8 @@ impl N5HTTPFetch {
         &self,
         path_name: &str,
         data_attrs: &wrapped::DatasetAttributes,
-        grid_position: Vec<u64>,
-    ) -> Promise {
+        grid_position: Coordinates,
+    ) -> PromiseEtag {
         N5PromiseEtagReader::block_etag(
             self, path_name, data_attrs, grid_position)
     }
@@ -166,155 +516,190 @@ impl N5HTTPFetch {
         &self,
         path_name: &str,
         data_attrs: &wrapped::DatasetAttributes,
-        grid_position: Vec<u64>
-    ) -> Promise {
+        grid_position: Coordinates
+    ) -> PromiseDataBlock {
         N5PromiseEtagReader::read_block_with_etag(
             self, path_name, data_attrs, grid_position)
     }
+
+    pub fn get_object_dataset_attributes(&self, path_name: &str) -> PromiseObjectDatasetAttributes {
+        N5PromiseObjectReader::get_object_dataset_attributes(self, path_name)
+    }
+
+    pub fn read_object_block(&self, path_name: &str, grid_position: Coordinates) -> PromiseBytes {
+        N5PromiseObjectReader::read_object_block(self, path_name, grid_position)
+    }
 }
 
+region_reader_delegations!(N5HTTPFetch);
+
+#[async_trait(?Send)]
 impl N5AsyncReader for N5HTTPFetch {
-    fn get_version(&self) -> Box<dyn Future<Item = n5::Version, Error = Error>> {
-        let to_return = self.get_attributes("")
-            .and_then(|attr| {
-                let ver = attr.get(n5::VERSION_ATTRIBUTE_KEY)
-                    .ok_or_else(|| Error::new(ErrorKind::NotFound, "Not an N5 root"))?;
-                Ok(n5::Version::from_str(ver.as_str().unwrap_or("")).unwrap())
-            });
+    async fn get_version(&self) -> Result<n5::Version, Error> {
+        let attr = self.get_attributes("").await?;
+        let ver = attr.get(n5::VERSION_ATTRIBUTE_KEY)
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "Not an N5 root"))?;
 
-        Box::new(to_return)
+        Ok(n5::Version::from_str(ver.as_str().unwrap_or("")).unwrap())
     }
 
-    fn get_dataset_attributes(&self, path_name: &str) ->
-            Box<dyn Future<Item = n5::DatasetAttributes, Error = Error>> {
-
-        let path = self.get_dataset_attributes_path(path_name);
-        let to_return = self
-            .fetch_json(&path)
-            .map(|json| { json.into_serde().unwrap() });
+    async fn get_dataset_attributes(&self, path_name: &str) -> Result<n5::DatasetAttributes, Error> {
+        let path = get_dataset_attributes_path(path_name);
+        let json = map_future_error_rust(self.fetch_json(&path)).await?;
 
-        Box::new(map_future_error_rust(to_return))
+        Ok(json.into_serde().unwrap())
     }
 
-    fn exists(&self, path_name: &str) -> Box<dyn Future<Item = bool, Error = Error>> {
-        let to_return = self.fetch(path_name).and_then(|resp_value| {
-            assert!(resp_value.is_instance_of::<Response>());
-            let resp: Response = resp_value.dyn_into().unwrap();
+    async fn exists(&self, path_name: &str) -> Result<bool, Error> {
+        let resp = map_future_error_rust(self.fetch(path_name)).await?;
+        if resp.ok() || self.primary().store == ObjectStore::Plain {
+            return Ok(resp.ok());
+        }
 
-            future::ok(resp.ok())
-        });
+        // Object stores have no directories to request, so groups exist if
+        // they have attributes or children.
+        let attributes = get_dataset_attributes_path(path_name);
+        let resp = map_future_error_rust(self.request(&attributes, "HEAD")).await?;
 
-        Box::new(map_future_error_rust(to_return))
+        Ok(resp.ok() || !N5AsyncReader::list(self, path_name).await?.is_empty())
     }
 
     // Override the default N5AsyncReader impl to not require the GET on the
     // dataset directory path to be 200.
-    fn dataset_exists(&self, path_name: &str) -> Box<dyn Future<Item = bool, Error = Error>> {
-        let path = self.get_dataset_attributes_path(path_name);
-        N5AsyncReader::exists(self, &path)
+    async fn dataset_exists(&self, path_name: &str) -> Result<bool, Error> {
+        let path = get_dataset_attributes_path(path_name);
+        N5AsyncReader::exists(self, &path).await
     }
 
-    fn read_block<T>(
+    async fn read_block_with_mode<T>(
         &self,
         path_name: &str,
         data_attrs: &DatasetAttributes,
         grid_position: GridCoord,
-    ) -> Box<dyn Future<Item = Option<VecDataBlock<T>>, Error = Error>>
+    ) -> Result<Option<(VecDataBlock<T>, BlockMode)>, Error>
         where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
             T: ReflectedType,
     {
 
-        Box::new(N5AsyncEtagReader::read_block_with_etag(
-                self, path_name, data_attrs, grid_position)
-            .map(|maybe_block| maybe_block.map(|(block, _etag)| block)))
+        // Spread reads across mirrors. Reads with etags use the primary,
+        // since mirrors may not agree on etags.
+        self.read_block_from(self.next_mirror(), path_name, data_attrs, grid_position).await
+            .map(|maybe_block| maybe_block.map(|(block, mode, _etag)| (block, mode)))
     }
 
-    fn list(&self, _path_name: &str) -> Box<dyn Future<Item = Vec<String>, Error = Error>> {
-        // TODO: Not implemented because remote paths are not listable.
-        unimplemented!()
+    /// Plain HTTP paths are not listable, so this is only supported for
+    /// containers in GCS or Azure.
+    async fn list(&self, path_name: &str) -> Result<Vec<String>, Error> {
+        map_future_error_rust(self.list_store(path_name)).await
     }
 
-    fn list_attributes(
+    async fn list_attributes(
         &self,
         path_name: &str,
-    ) -> Box<dyn Future<Item = serde_json::Value, Error = Error>> {
+    ) -> Result<serde_json::Value, Error> {
 
-        Box::new(self.get_attributes(path_name))
+        self.get_attributes(path_name).await
     }
 }
 
+#[async_trait(?Send)]
 impl N5AsyncEtagReader for N5HTTPFetch {
-    fn block_etag(
+    async fn block_etag(
         &self,
         path_name: &str,
         _data_attrs: &DatasetAttributes,
         grid_position: GridCoord,
-    ) -> Box<dyn Future<Item = Option<String>, Error = Error>> {
-        let mut request_options = RequestInit::new();
-        request_options.method("HEAD");
-        request_options.mode(RequestMode::Cors);
-
-        let block_path = self.relative_block_path(path_name, &grid_position);
+    ) -> Result<Option<String>, Error> {
+        let block_path = relative_block_path(path_name, &grid_position);
 
-        let req = Request::new_with_str_and_init(
-            &format!("{}/{}", &self.base_path, block_path),
-            &request_options).unwrap();
+        let resp = map_future_error_rust(self.request(&block_path, "HEAD")).await?;
 
-        let req_promise = self_().unwrap().fetch_with_request(&req);
+        if resp.ok() {
+            Ok(resp.headers().get("ETag").unwrap_or(None))
+        } else if self.is_missing(&resp) {
+            Ok(None)
+        } else {
+            Err(Error::new(
+                ErrorKind::Other,
+                format!("Request for block {} failed with status {}", block_path, resp.status())))
+        }
+    }
 
-        let f = JsFuture::from(req_promise)
-            .map(|resp_value| {
-                assert!(resp_value.is_instance_of::<Response>());
-                let resp: Response = resp_value.dyn_into().unwrap();
+    async fn read_block_with_mode_and_etag<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<Option<(VecDataBlock<T>, BlockMode, Option<String>)>, Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: ReflectedType,
+    {
 
-                if resp.ok() {
-                    resp.headers().get("ETag").unwrap_or(None)
-                } else {
-                    None
-                }
-            });
+        // Always start from the primary, so that etags of a block can be
+        // compared between reads even if mirrors compute them differently.
+        self.read_block_from(0, path_name, data_attrs, grid_position).await
+    }
+}
 
-        Box::new(map_future_error_rust(f))
+impl N5HTTPFetch {
+    /// Fetch the serialized bytes and etag of a block, starting from a given
+    /// mirror.
+    async fn fetch_block_bytes(
+        &self,
+        first_mirror: usize,
+        path_name: &str,
+        grid_position: &[u64],
+    ) -> Result<Option<(Vec<u8>, Option<String>)>, Error> {
+        let block_path = relative_block_path(path_name, grid_position);
+
+        let resp = map_future_error_rust(self.request_from(first_mirror, &block_path, "GET")).await?;
+
+        if resp.ok() {
+            let etag: Option<String> = resp.headers().get("ETag").unwrap_or(None);
+            let arrbuff_value = map_future_error_rust(
+                JsFuture::from(resp.array_buffer().unwrap())).await?;
+            assert!(arrbuff_value.is_instance_of::<ArrayBuffer>());
+            let typebuff: js_sys::Uint8Array = js_sys::Uint8Array::new(&arrbuff_value);
+
+            Ok(Some((typebuff.to_vec(), etag)))
+        } else if self.is_missing(&resp) {
+            Ok(None)
+        } else {
+            Err(Error::new(
+                ErrorKind::Other,
+                format!("Request for block {} failed with status {}", block_path, resp.status())))
+        }
     }
 
-    fn read_block_with_etag<T>(
+    async fn read_block_from<T>(
         &self,
+        first_mirror: usize,
         path_name: &str,
         data_attrs: &DatasetAttributes,
         grid_position: GridCoord,
-    ) -> Box<dyn Future<Item = Option<(VecDataBlock<T>, Option<String>)>, Error = Error>>
+    ) -> Result<Option<(VecDataBlock<T>, BlockMode, Option<String>)>, Error>
             where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
                 T: ReflectedType,
     {
+        match self.fetch_block_bytes(first_mirror, path_name, &grid_position).await? {
+            Some((buff, etag)) => {
+                let (block, mode) = decode_block(&buff, data_attrs, grid_position)?;
 
-        let da2 = data_attrs.clone();
-
-        let block_path = self.relative_block_path(path_name, &grid_position);
-
-        let f = self.fetch(&block_path).and_then(|resp_value| {
-            assert!(resp_value.is_instance_of::<Response>());
-            let resp: Response = resp_value.dyn_into().unwrap();
-
-            if resp.ok() {
-                let etag: Option<String> = resp.headers().get("ETag").unwrap_or(None);
-                let to_return = JsFuture::from(resp.array_buffer().unwrap())
-                    .map(move |arrbuff_value| {
-                        assert!(arrbuff_value.is_instance_of::<ArrayBuffer>());
-                        let typebuff: js_sys::Uint8Array = js_sys::Uint8Array::new(&arrbuff_value);
-                        let buff = typebuff.to_vec();
-
-                        Some((<n5::DefaultBlock as n5::DefaultBlockReader<T, &[u8]>>::read_block(
-                            &buff,
-                            &da2,
-                            grid_position).unwrap(),
-                            etag))
-                    });
-                future::Either::A(to_return)
-            } else  {
-                future::Either::B(future::ok(None))
-            }
-        });
+                Ok(Some((block, mode, etag)))
+            },
+            None => Ok(None),
+        }
+    }
+}
 
-        Box::new(map_future_error_rust(f))
+#[async_trait(?Send)]
+impl N5AsyncObjectReader for N5HTTPFetch {
+    async fn read_block_bytes(
+        &self,
+        path_name: &str,
+        grid_position: &[u64],
+    ) -> Result<Option<Vec<u8>>, Error> {
+        self.fetch_block_bytes(self.next_mirror(), path_name, grid_position).await
+            .map(|maybe_block| maybe_block.map(|(buff, _etag)| buff))
     }
 }
@@ -0,0 +1,193 @@
+use std::str::FromStr;
+
+use reqwest::header::ETAG;
+use reqwest::{
+    Client,
+    Response,
+    StatusCode,
+};
+
+use super::*;
+
+
+/// Native (non-WASM) counterpart to `N5HTTPFetch`, for using the same async
+/// reader traits from server-side tools, CLIs and native tests.
+#[derive(Clone)]
+pub struct N5HTTPNative {
+    base_path: String,
+    client: Client,
+}
+
+impl N5HTTPNative {
+    /// Open a container, checking that its N5 version is compatible.
+    pub async fn open(base_path: &str) -> Result<N5HTTPNative, Error> {
+        let reader = N5HTTPNative {
+            base_path: base_path.into(),
+            client: Client::new(),
+        };
+
+        let version = N5AsyncReader::get_version(&reader).await?;
+
+        if !n5::is_version_compatible(&n5::VERSION, &version) {
+            return Err(Error::new(ErrorKind::Other, "TODO: Incompatible version"))
+        }
+
+        Ok(reader)
+    }
+
+    async fn fetch(&self, path_name: &str) -> Result<Response, Error> {
+        self.client
+            .get(&format!("{}/{}", &self.base_path, path_name))
+            .send()
+            .await
+            .map_err(convert_reqwest_error)
+    }
+
+    async fn fetch_json(&self, path_name: &str) -> Result<serde_json::Value, Error> {
+        let resp = self.fetch(path_name).await?;
+        if !response_found(&resp, path_name)? {
+            return Err(Error::new(ErrorKind::NotFound, format!("{} not found", path_name)));
+        }
+
+        resp.json::<serde_json::Value>()
+            .await
+            .map_err(convert_reqwest_error)
+    }
+
+    async fn get_attributes(&self, path_name: &str) -> Result<serde_json::Value, Error> {
+        let path = get_dataset_attributes_path(path_name);
+        self.fetch_json(&path).await
+    }
+}
+
+fn convert_reqwest_error(error: reqwest::Error) -> Error {
+    Error::new(ErrorKind::Other, error)
+}
+
+/// Whether a response found its resource. Missing resources are 404s; any
+/// other failure is an error rather than an absent resource.
+fn response_found(resp: &Response, path_name: &str) -> Result<bool, Error> {
+    let status = resp.status();
+    if status.is_success() {
+        Ok(true)
+    } else if status == StatusCode::NOT_FOUND {
+        Ok(false)
+    } else {
+        Err(Error::new(
+            ErrorKind::Other,
+            format!("Request for {} failed with status {}", path_name, status)))
+    }
+}
+
+fn response_etag(resp: &Response) -> Option<String> {
+    resp.headers().get(ETAG)
+        .and_then(|etag| etag.to_str().ok())
+        .map(ToOwned::to_owned)
+}
+
+#[async_trait(?Send)]
+impl N5AsyncReader for N5HTTPNative {
+    async fn get_version(&self) -> Result<n5::Version, Error> {
+        let attr = self.get_attributes("").await?;
+        let ver = attr.get(n5::VERSION_ATTRIBUTE_KEY)
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "Not an N5 root"))?;
+
+        n5::Version::from_str(ver.as_str().unwrap_or(""))
+            .map_err(|error| Error::new(ErrorKind::InvalidData, format!("Invalid N5 version: {}", error)))
+    }
+
+    async fn get_dataset_attributes(&self, path_name: &str) -> Result<n5::DatasetAttributes, Error> {
+        let json = self.get_attributes(path_name).await?;
+
+        serde_json::from_value(json).map_err(Error::from)
+    }
+
+    async fn exists(&self, path_name: &str) -> Result<bool, Error> {
+        let resp = self.fetch(path_name).await?;
+
+        Ok(resp.status().is_success())
+    }
+
+    // Override the default N5AsyncReader impl to not require the GET on the
+    // dataset directory path to be 200.
+    async fn dataset_exists(&self, path_name: &str) -> Result<bool, Error> {
+        let path = get_dataset_attributes_path(path_name);
+        N5AsyncReader::exists(self, &path).await
+    }
+
+    async fn read_block_with_mode<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<Option<(VecDataBlock<T>, BlockMode)>, Error>
+        where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+            T: ReflectedType,
+    {
+
+        N5AsyncEtagReader::read_block_with_mode_and_etag(self, path_name, data_attrs, grid_position).await
+            .map(|maybe_block| maybe_block.map(|(block, mode, _etag)| (block, mode)))
+    }
+
+    /// Plain HTTP paths are not listable.
+    async fn list(&self, _path_name: &str) -> Result<Vec<String>, Error> {
+        Err(Error::new(ErrorKind::Other, "listing is not supported over HTTP"))
+    }
+
+    async fn list_attributes(
+        &self,
+        path_name: &str,
+    ) -> Result<serde_json::Value, Error> {
+
+        self.get_attributes(path_name).await
+    }
+}
+
+#[async_trait(?Send)]
+impl N5AsyncEtagReader for N5HTTPNative {
+    async fn block_etag(
+        &self,
+        path_name: &str,
+        _data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<Option<String>, Error> {
+        let block_path = relative_block_path(path_name, &grid_position);
+
+        let resp = self.client
+            .head(&format!("{}/{}", &self.base_path, block_path))
+            .send()
+            .await
+            .map_err(convert_reqwest_error)?;
+
+        if response_found(&resp, &block_path)? {
+            Ok(response_etag(&resp))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn read_block_with_mode_and_etag<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<Option<(VecDataBlock<T>, BlockMode, Option<String>)>, Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: ReflectedType,
+    {
+
+        let block_path = relative_block_path(path_name, &grid_position);
+
+        let resp = self.fetch(&block_path).await?;
+
+        if response_found(&resp, &block_path)? {
+            let etag = response_etag(&resp);
+            let bytes = resp.bytes().await.map_err(convert_reqwest_error)?;
+            let (block, mode) = decode_block(&bytes[..], data_attrs, grid_position)?;
+
+            Ok(Some((block, mode, etag)))
+        } else {
+            Ok(None)
+        }
+    }
+}
@@ -0,0 +1,404 @@
+//! In-memory N5 container, for scratch data and for testing code built on the
+//! async reader and writer traits without a server.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use super::*;
+use crate::object::{
+    N5AsyncObjectReader,
+    N5AsyncObjectWriter,
+    N5PromiseObjectReader,
+    N5PromiseObjectWriter,
+};
+
+
+#[derive(Default)]
+struct MemoryContainer {
+    /// Attributes of each group, keyed by normalized path. Groups exist iff
+    /// they have an entry here, even if it is empty.
+    attributes: HashMap<String, serde_json::Map<String, serde_json::Value>>,
+    /// Serialized blocks keyed by block path, with the generation at which
+    /// each was written.
+    blocks: HashMap<String, (Vec<u8>, u64)>,
+    generation: u64,
+}
+
+fn normalize_path(path_name: &str) -> String {
+    path_name.trim_matches('/').to_owned()
+}
+
+fn is_descendant(path: &str, ancestor: &str) -> bool {
+    ancestor.is_empty() ||
+        (path.starts_with(ancestor) && path[ancestor.len()..].starts_with('/'))
+}
+
+
+/// N5 container held in memory. Clones share the same container.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct N5InMemory {
+    container: Rc<RefCell<MemoryContainer>>,
+}
+
+impl Default for N5InMemory {
+    fn default() -> Self {
+        let mut root = serde_json::Map::new();
+        root.insert(
+            n5::VERSION_ATTRIBUTE_KEY.to_owned(),
+            serde_json::Value::String(n5::VERSION.to_string()));
+
+        let mut container = MemoryContainer::default();
+        container.attributes.insert(String::new(), root);
+
+        N5InMemory {
+            container: Rc::new(RefCell::new(container)),
+        }
+    }
+}
+
+impl N5InMemory {
+    fn get_attributes(&self, path_name: &str) -> Result<serde_json::Map<String, serde_json::Value>, Error> {
+        self.container.borrow().attributes.get(&normalize_path(path_name))
+            .cloned()
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "Group not found"))
+    }
+
+    fn block_bytes(&self, path_name: &str, grid_position: &[u64]) -> Option<(Vec<u8>, u64)> {
+        let block_path = relative_block_path(&normalize_path(path_name), grid_position);
+        self.container.borrow().blocks.get(&block_path).cloned()
+    }
+
+    fn put_block_bytes(&self, path_name: &str, grid_position: &[u64], bytes: Vec<u8>) {
+        let block_path = relative_block_path(&normalize_path(path_name), grid_position);
+
+        let mut container = self.container.borrow_mut();
+        container.generation += 1;
+        let generation = container.generation;
+        container.blocks.insert(block_path, (bytes, generation));
+    }
+}
+
+#[wasm_bindgen]
+impl N5InMemory {
+    /// Create an empty container.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> N5InMemory {
+        N5InMemory::default()
+    }
+}
+
+/// Delegations to expose N5PromiseReader, N5PromiseEtagReader,
+/// N5PromiseWriter and object dataset traits to WASM.
+#[wasm_bindgen]
+impl N5InMemory {
+    pub fn get_version(&self) -> PromiseVersion {
+        N5PromiseReader::get_version(self)
+    }
+
+    pub fn get_dataset_attributes(&self, path_name: &str) -> PromiseDatasetAttributes {
+        N5PromiseReader::get_dataset_attributes(self, path_name)
+    }
+
+    pub fn exists(&self, path_name: &str) -> PromiseBool {
+        N5PromiseReader::exists(self, path_name)
+    }
+
+    pub fn dataset_exists(&self, path_name: &str) -> PromiseBool {
+        N5PromiseReader::dataset_exists(self, path_name)
+    }
+
+    pub fn read_block(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        grid_position: Coordinates,
+    ) -> PromiseDataBlock {
+        N5PromiseReader::read_block(self, path_name, data_attrs, grid_position)
+    }
+
+    pub fn iter_blocks(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        offset: Coordinates,
+        size: Coordinates,
+    ) -> Result<DataBlockAsyncIterator, JsValue> {
+        N5PromiseReader::iter_blocks(self, path_name, data_attrs, offset, size)
+    }
+
+    pub fn list(&self, path_name: &str) -> PromiseStrings {
+        N5PromiseReader::list(self, path_name)
+    }
+
+    pub fn list_attributes(&self, path_name: &str) -> PromiseAttributes {
+        N5PromiseReader::list_attributes(self, path_name)
+    }
+
+    pub fn block_etag(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        grid_position: Coordinates,
+    ) -> PromiseEtag {
+        N5PromiseEtagReader::block_etag(
+            self, path_name, data_attrs, grid_position)
+    }
+
+    pub fn read_block_with_etag(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        grid_position: Coordinates
+    ) -> PromiseDataBlock {
+        N5PromiseEtagReader::read_block_with_etag(
+            self, path_name, data_attrs, grid_position)
+    }
+
+    pub fn set_attributes(&self, path_name: &str, attributes: AttributesJson) -> PromiseVoid {
+        N5PromiseWriter::set_attributes(self, path_name, attributes)
+    }
+
+    pub fn create_group(&self, path_name: &str) -> PromiseVoid {
+        N5PromiseWriter::create_group(self, path_name)
+    }
+
+    pub fn create_dataset(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+    ) -> PromiseVoid {
+        N5PromiseWriter::create_dataset(self, path_name, data_attrs)
+    }
+
+    pub fn remove(&self, path_name: &str) -> PromiseVoid {
+        N5PromiseWriter::remove(self, path_name)
+    }
+
+    pub fn write_block(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        grid_position: Coordinates,
+        data: BlockData,
+    ) -> PromiseVoid {
+        N5PromiseWriter::write_block(self, path_name, data_attrs, grid_position, data)
+    }
+
+    pub fn delete_block(&self, path_name: &str, grid_position: Coordinates) -> PromiseBool {
+        N5PromiseWriter::delete_block(self, path_name, grid_position)
+    }
+
+    pub fn get_object_dataset_attributes(&self, path_name: &str) -> PromiseObjectDatasetAttributes {
+        N5PromiseObjectReader::get_object_dataset_attributes(self, path_name)
+    }
+
+    pub fn read_object_block(&self, path_name: &str, grid_position: Coordinates) -> PromiseBytes {
+        N5PromiseObjectReader::read_object_block(self, path_name, grid_position)
+    }
+
+    pub fn create_object_dataset(&self, path_name: &str, data_attrs: AttributesJson) -> PromiseVoid {
+        N5PromiseObjectWriter::create_object_dataset(self, path_name, data_attrs)
+    }
+
+    pub fn write_object_block(
+        &self,
+        path_name: &str,
+        grid_position: Coordinates,
+        data: Vec<u8>,
+    ) -> PromiseVoid {
+        N5PromiseObjectWriter::write_object_block(self, path_name, grid_position, data)
+    }
+}
+
+region_reader_delegations!(N5InMemory);
+
+#[async_trait(?Send)]
+impl N5AsyncReader for N5InMemory {
+    async fn get_version(&self) -> Result<n5::Version, Error> {
+        let attr = self.get_attributes("")?;
+        let ver = attr.get(n5::VERSION_ATTRIBUTE_KEY)
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "Not an N5 root"))?;
+
+        n5::Version::from_str(ver.as_str().unwrap_or(""))
+            .map_err(|error| Error::new(ErrorKind::InvalidData, format!("Invalid N5 version: {}", error)))
+    }
+
+    async fn get_dataset_attributes(&self, path_name: &str) -> Result<n5::DatasetAttributes, Error> {
+        let attr = self.get_attributes(path_name)?;
+
+        Ok(serde_json::from_value(serde_json::Value::Object(attr))?)
+    }
+
+    async fn exists(&self, path_name: &str) -> Result<bool, Error> {
+        Ok(self.container.borrow().attributes.contains_key(&normalize_path(path_name)))
+    }
+
+    async fn read_block_with_mode<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<Option<(VecDataBlock<T>, BlockMode)>, Error>
+        where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+            T: ReflectedType,
+    {
+        N5AsyncEtagReader::read_block_with_mode_and_etag(self, path_name, data_attrs, grid_position).await
+            .map(|maybe_block| maybe_block.map(|(block, mode, _etag)| (block, mode)))
+    }
+
+    /// Lists child groups and datasets, but not block directories.
+    async fn list(&self, path_name: &str) -> Result<Vec<String>, Error> {
+        let path = normalize_path(path_name);
+        let container = self.container.borrow();
+        if !container.attributes.contains_key(&path) {
+            return Err(Error::new(ErrorKind::NotFound, "Group not found"));
+        }
+
+        let prefix_len = if path.is_empty() { 0 } else { path.len() + 1 };
+        let mut children: Vec<String> = container.attributes.keys()
+            .filter(|child| !child.is_empty() && is_descendant(child, &path))
+            .map(|child| &child[prefix_len..])
+            .filter(|name| !name.contains('/'))
+            .map(ToOwned::to_owned)
+            .collect();
+        children.sort();
+
+        Ok(children)
+    }
+
+    async fn list_attributes(&self, path_name: &str) -> Result<serde_json::Value, Error> {
+        self.get_attributes(path_name).map(serde_json::Value::Object)
+    }
+}
+
+#[async_trait(?Send)]
+impl N5AsyncEtagReader for N5InMemory {
+    async fn block_etag(
+        &self,
+        path_name: &str,
+        _data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<Option<String>, Error> {
+        Ok(self.block_bytes(path_name, &grid_position)
+            .map(|(_, generation)| generation.to_string()))
+    }
+
+    async fn read_block_with_mode_and_etag<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<Option<(VecDataBlock<T>, BlockMode, Option<String>)>, Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: ReflectedType,
+    {
+        match self.block_bytes(path_name, &grid_position) {
+            Some((buff, generation)) => {
+                let (block, mode) = decode_block(&buff, data_attrs, grid_position)?;
+
+                Ok(Some((block, mode, Some(generation.to_string()))))
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl N5AsyncWriter for N5InMemory {
+    async fn set_attributes(
+        &self,
+        path_name: &str,
+        attributes: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<(), Error> {
+        self.create_group(path_name).await?;
+
+        let mut container = self.container.borrow_mut();
+        let existing = container.attributes.get_mut(&normalize_path(path_name))
+            .expect("Group was just created");
+        merge_attributes(existing, attributes);
+
+        Ok(())
+    }
+
+    async fn create_group(&self, path_name: &str) -> Result<(), Error> {
+        let path = normalize_path(path_name);
+        let mut container = self.container.borrow_mut();
+
+        let mut ancestor = String::new();
+        for component in path.split('/').filter(|c| !c.is_empty()) {
+            if !ancestor.is_empty() {
+                ancestor.push('/');
+            }
+            ancestor.push_str(component);
+            container.attributes.entry(ancestor.clone()).or_default();
+        }
+
+        Ok(())
+    }
+
+    async fn remove(&self, path_name: &str) -> Result<(), Error> {
+        let path = normalize_path(path_name);
+        if path.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidInput, "Can not remove the container root"));
+        }
+        let mut container = self.container.borrow_mut();
+
+        container.attributes.retain(|group, _| group != &path && !is_descendant(group, &path));
+        container.blocks.retain(|block, _| !is_descendant(block, &path));
+
+        Ok(())
+    }
+
+    async fn write_block<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        block: &VecDataBlock<T>,
+    ) -> Result<(), Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::WriteableDataBlock,
+                T: ReflectedType,
+    {
+        let bytes = encode_block(data_attrs, block)?;
+        self.put_block_bytes(path_name, block.get_grid_position(), bytes);
+
+        Ok(())
+    }
+
+    async fn delete_block(
+        &self,
+        path_name: &str,
+        grid_position: &[u64],
+    ) -> Result<bool, Error> {
+        let block_path = relative_block_path(&normalize_path(path_name), grid_position);
+
+        Ok(self.container.borrow_mut().blocks.remove(&block_path).is_some())
+    }
+}
+
+#[async_trait(?Send)]
+impl N5AsyncObjectReader for N5InMemory {
+    async fn read_block_bytes(
+        &self,
+        path_name: &str,
+        grid_position: &[u64],
+    ) -> Result<Option<Vec<u8>>, Error> {
+        Ok(self.block_bytes(path_name, grid_position).map(|(buff, _)| buff))
+    }
+}
+
+#[async_trait(?Send)]
+impl N5AsyncObjectWriter for N5InMemory {
+    async fn write_block_bytes(
+        &self,
+        path_name: &str,
+        grid_position: &[u64],
+        bytes: Vec<u8>,
+    ) -> Result<(), Error> {
+        self.put_block_bytes(path_name, grid_position, bytes);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,236 @@
+//! Enumeration of the labels of segmentations, with each label's voxel count
+//! and bounding box, merged across blocks.
+
+use std::collections::BTreeMap;
+
+use super::*;
+
+
+#[wasm_bindgen(typescript_custom_section)]
+const LABEL_TYPES: &str = r#"
+/** Inclusive bounding box of a label's voxels. */
+export interface LabelBounds {
+  min: number[];
+  max: number[];
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "LabelBounds")]
+    pub type LabelBoundsJson;
+
+    #[wasm_bindgen(typescript_type = "Promise<LabelSummary>")]
+    pub type PromiseLabelSummary;
+}
+
+/// Voxel count and inclusive bounding box of a label.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LabelInfo {
+    pub count: u64,
+    pub min: Vec<u64>,
+    pub max: Vec<u64>,
+}
+
+/// The labels present in some data, other than background label 0.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LabelSummary {
+    pub labels: BTreeMap<u64, LabelInfo>,
+}
+
+impl LabelSummary {
+    /// Add a voxel of a label at some coordinates.
+    pub fn add(&mut self, label: u64, coord: &[u64]) {
+        if label == 0 {
+            return;
+        }
+
+        match self.labels.get_mut(&label) {
+            Some(info) => {
+                info.count += 1;
+                for ((min, max), &c) in info.min.iter_mut().zip(&mut info.max).zip(coord) {
+                    *min = (*min).min(c);
+                    *max = (*max).max(c);
+                }
+            },
+            None => {
+                self.labels.insert(label, LabelInfo {
+                    count: 1,
+                    min: coord.to_vec(),
+                    max: coord.to_vec(),
+                });
+            },
+        }
+    }
+
+    /// Merge the labels of other data, such as of another block.
+    pub fn merge(&mut self, other: LabelSummary) {
+        for (label, other_info) in other.labels {
+            match self.labels.get_mut(&label) {
+                Some(info) => {
+                    info.count += other_info.count;
+                    for (min, &other_min) in info.min.iter_mut().zip(&other_info.min) {
+                        *min = (*min).min(other_min);
+                    }
+                    for (max, &other_max) in info.max.iter_mut().zip(&other_info.max) {
+                        *max = (*max).max(other_max);
+                    }
+                },
+                None => {
+                    self.labels.insert(label, other_info);
+                },
+            }
+        }
+    }
+}
+
+/// The labels of a block within a region of voxels, with coordinates in the
+/// dataset.
+fn region_block_labels<T>(
+    data_attrs: &DatasetAttributes,
+    block: &VecDataBlock<T>,
+    offset: &[u64],
+    size: &[u64],
+) -> LabelSummary
+        where VecDataBlock<T>: DataBlock<T>,
+            T: Copy + Into<u64>,
+{
+    let mut summary = LabelSummary::default();
+    let mut coord = vec![0; offset.len()];
+    for_each_in_region(data_attrs, block, offset, size, |region_coord, &label| {
+        for ((c, &r), &o) in coord.iter_mut().zip(region_coord).zip(offset) {
+            *c = r + o;
+        }
+        summary.add(label.into(), &coord);
+    });
+
+    summary
+}
+
+/// The labels of a block of a dataset, with coordinates in the dataset.
+pub fn block_labels<T>(
+    data_attrs: &DatasetAttributes,
+    block: &VecDataBlock<T>,
+) -> LabelSummary
+        where VecDataBlock<T>: DataBlock<T>,
+            T: Copy + Into<u64>,
+{
+    let offset = vec![0; data_attrs.get_ndim()];
+    region_block_labels(data_attrs, block, &offset, data_attrs.get_dimensions())
+}
+
+/// Enumerating the labels of regions of segmentations, for any reader.
+#[async_trait(?Send)]
+pub trait N5AsyncLabelReader: N5AsyncReader {
+    /// The labels in a region of voxels, clamped to the dataset, merged
+    /// across its blocks. Missing blocks have no labels.
+    async fn region_labels<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        offset: &[u64],
+        size: &[u64],
+    ) -> Result<LabelSummary, Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: ReflectedType + Copy + Into<u64>,
+    {
+        let grid_positions = region_grid_positions(data_attrs, offset, size)?;
+        let blocks = futures::future::try_join_all(grid_positions.into_iter()
+            .map(|grid_position| self.read_block::<T>(path_name, data_attrs, grid_position.into())))
+            .await?;
+
+        let mut summary = LabelSummary::default();
+        for block in blocks.iter().flatten() {
+            summary.merge(region_block_labels(data_attrs, block, offset, size));
+        }
+
+        Ok(summary)
+    }
+}
+
+impl<R: N5AsyncReader> N5AsyncLabelReader for R {}
+
+
+pub trait N5PromiseLabelReader {
+    /// Resolve to the `LabelSummary` of a region of voxels of an unsigned
+    /// integer dataset.
+    fn region_labels(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        offset: Coordinates,
+        size: Coordinates,
+    ) -> PromiseLabelSummary;
+}
+
+impl<T> N5PromiseLabelReader for T where T: N5AsyncReader + Clone + 'static {
+    fn region_labels(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        offset: Coordinates,
+        size: Coordinates,
+    ) -> PromiseLabelSummary {
+
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let data_attrs = data_attrs.0.clone();
+        let offset = offset.to_vec();
+        let size = size.to_vec();
+
+        let to_return = async move {
+            let (offset, size) = (offset?, size?);
+            let summary = match data_attrs.get_data_type() {
+                DataType::UINT8 => N5AsyncLabelReader::region_labels::<u8>(
+                    &reader, &path_name, &data_attrs, &offset, &size).await?,
+                DataType::UINT16 => N5AsyncLabelReader::region_labels::<u16>(
+                    &reader, &path_name, &data_attrs, &offset, &size).await?,
+                DataType::UINT32 => N5AsyncLabelReader::region_labels::<u32>(
+                    &reader, &path_name, &data_attrs, &offset, &size).await?,
+                DataType::UINT64 => N5AsyncLabelReader::region_labels::<u64>(
+                    &reader, &path_name, &data_attrs, &offset, &size).await?,
+                data_type => return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Labels must be unsigned integers, not {}", data_type))),
+            };
+            Ok(JsValue::from(JsLabelSummary(summary)))
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
+}
+
+
+/// The labels of a region, as exposed to JS.
+#[wasm_bindgen(js_name = LabelSummary)]
+pub struct JsLabelSummary(LabelSummary);
+
+#[wasm_bindgen(js_class = LabelSummary)]
+impl JsLabelSummary {
+    /// The labels present, in increasing order.
+    #[wasm_bindgen(getter)]
+    pub fn labels(&self) -> Vec<u64> {
+        self.0.labels.keys().copied().collect()
+    }
+
+    /// The voxel count of each of `labels`.
+    #[wasm_bindgen(getter)]
+    pub fn counts(&self) -> Vec<u64> {
+        self.0.labels.values().map(|info| info.count).collect()
+    }
+
+    /// The voxel count of a label, which is 0 if it is absent.
+    pub fn count(&self, label: Coordinate) -> Result<u64, JsValue> {
+        let label = label.to_u64().map_err(|error| js_sys::Error::new(&error.to_string()))?;
+        Ok(self.0.labels.get(&label).map_or(0, |info| info.count))
+    }
+
+    /// The bounding box of a label, or `undefined` if it is absent.
+    pub fn bounds(&self, label: Coordinate) -> Result<Option<LabelBoundsJson>, JsValue> {
+        let label = label.to_u64().map_err(|error| js_sys::Error::new(&error.to_string()))?;
+        Ok(self.0.labels.get(&label).map(|info| {
+            let bounds = serde_json::json!({ "min": info.min, "max": info.max });
+            JsValue::from_serde(&bounds).unwrap().unchecked_into()
+        }))
+    }
+}
@@ -1,3 +1,4 @@
+use async_trait::async_trait;
 use futures;
 use js_sys;
 use n5;
@@ -8,13 +9,16 @@ use web_sys;
 
 mod utils;
 
+use std::fmt::Write;
+use std::rc::Rc;
 use std::io::{
     Error,
     ErrorKind,
 };
 
 use js_sys::Promise;
-use futures::{future, Future};
+use futures::{Future, TryFutureExt};
+use wasm_bindgen::JsCast;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::future_to_promise;
 
@@ -22,84 +26,286 @@ use n5::prelude::*;
 use n5::{data_type_match, data_type_rstype_replace};
 
 
+/// Expose the traits for reading regions of datasets to WASM on a backend.
+/// Defined before the backend modules so that it is in scope for them.
+macro_rules! region_reader_delegations {
+    ($backend:ty) => {
+        /// Delegations to expose traits for reading regions of datasets to WASM.
+        #[wasm_bindgen]
+        impl $backend {
+            pub fn read_slice(
+                &self,
+                path_name: &str,
+                data_attrs: &crate::wrapped::DatasetAttributes,
+                axis: usize,
+                index: crate::typescript::Coordinate,
+                bounds: Option<crate::slice::SliceBoundsJson>,
+            ) -> crate::slice::PromiseSlice {
+                crate::slice::N5PromiseSliceReader::read_slice(self, path_name, data_attrs, axis, index, bounds)
+            }
+
+            pub fn reslice(
+                &self,
+                path_name: &str,
+                data_attrs: &crate::wrapped::DatasetAttributes,
+                plane: crate::reslice::PlaneJson,
+                interpolation: crate::reslice::Interpolation,
+            ) -> crate::reslice::PromiseReslice {
+                crate::reslice::N5PromiseResliceReader::reslice(self, path_name, data_attrs, plane, interpolation)
+            }
+
+            pub fn get_value(
+                &self,
+                path_name: &str,
+                data_attrs: &crate::wrapped::DatasetAttributes,
+                coord: crate::typescript::Coordinates,
+            ) -> crate::value::PromiseValue {
+                crate::value::N5PromiseValueReader::get_value(self, path_name, data_attrs, coord)
+            }
+
+            pub fn get_values(
+                &self,
+                path_name: &str,
+                data_attrs: &crate::wrapped::DatasetAttributes,
+                coords: crate::value::CoordinatesList,
+            ) -> crate::value::PromiseValues {
+                crate::value::N5PromiseValueReader::get_values(self, path_name, data_attrs, coords)
+            }
+
+            pub fn block_statistics(
+                &self,
+                path_name: &str,
+                data_attrs: &crate::wrapped::DatasetAttributes,
+                grid_position: crate::typescript::Coordinates,
+                options: Option<crate::stats::StatisticsOptionsJson>,
+            ) -> crate::stats::PromiseStatistics {
+                crate::stats::N5PromiseStatisticsReader::block_statistics(self, path_name, data_attrs, grid_position, options)
+            }
+
+            pub fn region_statistics(
+                &self,
+                path_name: &str,
+                data_attrs: &crate::wrapped::DatasetAttributes,
+                offset: crate::typescript::Coordinates,
+                size: crate::typescript::Coordinates,
+                options: Option<crate::stats::StatisticsOptionsJson>,
+            ) -> crate::stats::PromiseStatistics {
+                crate::stats::N5PromiseStatisticsReader::region_statistics(self, path_name, data_attrs, offset, size, options)
+            }
+
+            pub fn region_labels(
+                &self,
+                path_name: &str,
+                data_attrs: &crate::wrapped::DatasetAttributes,
+                offset: crate::typescript::Coordinates,
+                size: crate::typescript::Coordinates,
+            ) -> crate::labels::PromiseLabelSummary {
+                crate::labels::N5PromiseLabelReader::region_labels(self, path_name, data_attrs, offset, size)
+            }
+
+            pub fn read_region_downsampled(
+                &self,
+                path_name: &str,
+                data_attrs: &crate::wrapped::DatasetAttributes,
+                offset: crate::typescript::Coordinates,
+                size: crate::typescript::Coordinates,
+                factors: crate::typescript::Coordinates,
+                method: crate::downsample::Downsampling,
+            ) -> crate::downsample::PromiseDownsampledRegion {
+                crate::downsample::N5PromiseDownsampleReader::read_region_downsampled(
+                    self, path_name, data_attrs, offset, size, factors, method)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "futures01-compat")]
+pub mod compat;
+pub mod downsample;
+pub mod file_system;
 pub mod http_fetch;
+#[cfg(feature = "native")]
+pub mod http_native;
+pub mod in_memory;
+pub mod labels;
+pub mod object;
+pub mod overlay;
+pub mod render;
+pub mod reslice;
+pub mod s3;
+pub mod slice;
+pub mod stats;
+pub mod transport;
+pub mod typescript;
+pub mod value;
+pub mod zip;
+
+use crate::typescript::*;
+
+
+const ATTRIBUTES_FILE: &str = "attributes.json";
 
 
 pub trait N5PromiseReader {
     /// Get the N5 specification version of the container.
-    fn get_version(&self) -> Promise;
+    fn get_version(&self) -> PromiseVersion;
 
-    fn get_dataset_attributes(&self, path_name: &str) -> Promise;
+    fn get_dataset_attributes(&self, path_name: &str) -> PromiseDatasetAttributes;
 
-    fn exists(&self, path_name: &str) -> Promise;
+    fn exists(&self, path_name: &str) -> PromiseBool;
 
-    fn dataset_exists(&self, path_name: &str) -> Promise;
+    fn dataset_exists(&self, path_name: &str) -> PromiseBool;
 
     fn read_block(
         &self,
         path_name: &str,
         data_attrs: &wrapped::DatasetAttributes,
-        grid_position: Vec<u64>,
-    ) -> Promise;
+        grid_position: Coordinates,
+    ) -> PromiseDataBlock;
 
-    fn list_attributes(&self, path_name: &str) -> Promise;
-}
+    /// Iterate over the existing blocks intersecting a region of voxels,
+    /// in column-major order of their grid positions.
+    fn iter_blocks(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        offset: Coordinates,
+        size: Coordinates,
+    ) -> Result<DataBlockAsyncIterator, JsValue>;
+
+    /// List the child groups and datasets of a group. Not all backends
+    /// support listing.
+    fn list(&self, path_name: &str) -> PromiseStrings;
 
-impl<T> N5PromiseReader for T where T: N5AsyncReader {
-    fn get_version(&self) -> Promise {
-        let to_return = self.get_version()
-            .map(|v| JsValue::from(wrapped::Version(v)));
+    fn list_attributes(&self, path_name: &str) -> PromiseAttributes;
+}
 
-        future_to_promise(map_future_error_wasm(to_return))
+/// Promises must own everything they reference, so the reader is cloned into
+/// each returned promise.
+impl<T> N5PromiseReader for T where T: N5AsyncReader + Clone + 'static {
+    fn get_version(&self) -> PromiseVersion {
+        let reader = self.clone();
+        let to_return = async move {
+            reader.get_version().await
+                .map(|v| JsValue::from(wrapped::Version(v)))
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
     }
 
-    fn get_dataset_attributes(&self, path_name: &str) -> Promise {
-        let to_return = self.get_dataset_attributes(path_name)
-            .map(|da| JsValue::from(wrapped::DatasetAttributes(da)));
+    fn get_dataset_attributes(&self, path_name: &str) -> PromiseDatasetAttributes {
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let to_return = async move {
+            reader.get_dataset_attributes(&path_name).await
+                .map(|da| JsValue::from(wrapped::DatasetAttributes(da)))
+        };
 
-        future_to_promise(map_future_error_wasm(to_return))
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
     }
 
-    fn exists(&self, path_name: &str) -> Promise {
-        let to_return = self.exists(path_name)
-            .map(JsValue::from);
+    fn exists(&self, path_name: &str) -> PromiseBool {
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let to_return = async move {
+            reader.exists(&path_name).await
+                .map(JsValue::from)
+        };
 
-        future_to_promise(map_future_error_wasm(to_return))
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
     }
 
-    fn dataset_exists(&self, path_name: &str) -> Promise {
-        let to_return = self.dataset_exists(path_name)
-            .map(JsValue::from);
+    fn dataset_exists(&self, path_name: &str) -> PromiseBool {
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let to_return = async move {
+            reader.dataset_exists(&path_name).await
+                .map(JsValue::from)
+        };
 
-        future_to_promise(map_future_error_wasm(to_return))
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
     }
 
     fn read_block(
         &self,
         path_name: &str,
         data_attrs: &wrapped::DatasetAttributes,
-        grid_position: Vec<u64>,
-    ) -> Promise {
+        grid_position: Coordinates,
+    ) -> PromiseDataBlock {
+
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let data_attrs = data_attrs.0.clone();
+        let grid_position = grid_position.to_vec();
+        let to_return = async move {
+            reader.read_block_dyn(&path_name, &data_attrs, grid_position?.into()).await
+                .map(|maybe_block| maybe_block
+                    .map(|(block, mode)| block.into_js(mode, None))
+                    .unwrap_or(JsValue::UNDEFINED))
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
 
-        data_type_match! {
-            data_attrs.0.get_data_type(),
-            future_to_promise(map_future_error_wasm(
-                self.read_block::<RsType>(path_name, &data_attrs.0, grid_position.into())
-                    .map(|maybe_block| JsValue::from(
-                        maybe_block.map(<RsType as VecBlockMonomorphizerReflection>::MONOMORPH::from)))))
-        }
+    fn iter_blocks(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        offset: Coordinates,
+        size: Coordinates,
+    ) -> Result<DataBlockAsyncIterator, JsValue> {
+
+        let to_js_error = |error: Error| JsValue::from(js_sys::Error::new(&error.to_string()));
+        let data_attrs = data_attrs.0.clone();
+        let offset = offset.to_vec().map_err(to_js_error)?;
+        let size = size.to_vec().map_err(to_js_error)?;
+        let grid_positions = region_grid_positions(&data_attrs, &offset, &size)
+            .map_err(to_js_error)?;
+
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let read_block = move |grid_position: Vec<u64>| {
+            let reader = reader.clone();
+            let path_name = path_name.clone();
+            let data_attrs = data_attrs.clone();
+            let to_return = async move {
+                reader.read_block_dyn(&path_name, &data_attrs, grid_position.into()).await
+                    .map(|maybe_block| maybe_block
+                        .map(|(block, mode)| block.into_js(mode, None))
+                        .unwrap_or(JsValue::UNDEFINED))
+            };
+
+            future_to_promise(map_future_error_wasm(to_return))
+        };
+
+        Ok(BlockRegionIterator::new_js(grid_positions, Rc::new(read_block)))
+    }
+
+    fn list(&self, path_name: &str) -> PromiseStrings {
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let to_return = async move {
+            reader.list(&path_name).await
+                .map(|children| JsValue::from_serde(&children).unwrap())
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
     }
 
     fn list_attributes(
         &self,
         path_name: &str,
-    ) -> Promise {
+    ) -> PromiseAttributes {
 
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
         // TODO: Superfluous conversion from JSON to JsValue to serde to JsValue.
-        let to_return = self.list_attributes(path_name)
-            .map(|v| JsValue::from_serde(&v).unwrap());
+        let to_return = async move {
+            reader.list_attributes(&path_name).await
+                .map(|v| JsValue::from_serde(&v).unwrap())
+        };
 
-        future_to_promise(map_future_error_wasm(to_return))
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
     }
 }
 
@@ -109,44 +315,55 @@ pub trait N5PromiseEtagReader {
         &self,
         path_name: &str,
         data_attrs: &wrapped::DatasetAttributes,
-        grid_position: Vec<u64>,
-    ) -> Promise;
+        grid_position: Coordinates,
+    ) -> PromiseEtag;
 
     fn read_block_with_etag(
         &self,
         path_name: &str,
         data_attrs: &wrapped::DatasetAttributes,
-        grid_position: Vec<u64>,
-    ) -> Promise;
+        grid_position: Coordinates,
+    ) -> PromiseDataBlock;
 }
 
-impl<T> N5PromiseEtagReader for T where T: N5AsyncEtagReader {
+impl<T> N5PromiseEtagReader for T where T: N5AsyncEtagReader + Clone + 'static {
     fn block_etag(
         &self,
         path_name: &str,
         data_attrs: &wrapped::DatasetAttributes,
-        grid_position: Vec<u64>,
-    ) -> Promise {
-        let to_return = self.block_etag(path_name, &data_attrs.0, grid_position.into())
-            .map(JsValue::from);
-
-        future_to_promise(map_future_error_wasm(to_return))
+        grid_position: Coordinates,
+    ) -> PromiseEtag {
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let data_attrs = data_attrs.0.clone();
+        let grid_position = grid_position.to_vec();
+        let to_return = async move {
+            reader.block_etag(&path_name, &data_attrs, grid_position?.into()).await
+                .map(JsValue::from)
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
     }
 
     fn read_block_with_etag(
         &self,
         path_name: &str,
         data_attrs: &wrapped::DatasetAttributes,
-        grid_position: Vec<u64>,
-    ) -> Promise {
-
-        data_type_match! {
-            data_attrs.0.get_data_type(),
-            future_to_promise(map_future_error_wasm(
-                self.read_block_with_etag::<RsType>(path_name, &data_attrs.0, grid_position.into())
-                    .map(|maybe_block| JsValue::from(
-                        maybe_block.map(<RsType as VecBlockMonomorphizerReflection>::MONOMORPH::from)))))
-        }
+        grid_position: Coordinates,
+    ) -> PromiseDataBlock {
+
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let data_attrs = data_attrs.0.clone();
+        let grid_position = grid_position.to_vec();
+        let to_return = async move {
+            reader.read_block_with_etag_dyn(&path_name, &data_attrs, grid_position?.into()).await
+                .map(|maybe_block| maybe_block
+                    .map(|(block, mode, etag)| block.into_js(mode, etag))
+                    .unwrap_or(JsValue::UNDEFINED))
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
     }
 }
 
@@ -154,63 +371,297 @@ impl<T> N5PromiseEtagReader for T where T: N5AsyncEtagReader {
 /// This trait exists to preserve type information between calls (rather than
 /// erasing it with `Promise`) and for easier potential future compatibility
 /// with an N5 core async trait.
+///
+/// Futures are not required to be `Send`, since JS values never are.
+#[async_trait(?Send)]
 pub trait N5AsyncReader {
-    fn get_version(&self) -> Box<dyn Future<Item = n5::Version, Error = Error>>;
+    async fn get_version(&self) -> Result<n5::Version, Error>;
+
+    async fn get_dataset_attributes(&self, path_name: &str) -> Result<n5::DatasetAttributes, Error>;
 
-    fn get_dataset_attributes(&self, path_name: &str) ->
-        Box<dyn Future<Item = n5::DatasetAttributes, Error = Error>>;
+    async fn exists(&self, path_name: &str) -> Result<bool, Error>;
 
-    fn exists(&self, path_name: &str) -> Box<dyn Future<Item = bool, Error = Error>>;
+    async fn dataset_exists(&self, path_name: &str) -> Result<bool, Error> {
+        let (exists, has_attr) = futures::join!(
+            self.exists(path_name),
+            self.get_dataset_attributes(path_name));
 
-    fn dataset_exists(&self, path_name: &str) -> Box<dyn Future<Item = bool, Error = Error>> {
-        Box::new(self.exists(path_name).join(
-            self.get_dataset_attributes(path_name)
-                .map(|_| true)
-                .or_else(|_| futures::future::ok(false))
-        ).map(|(exists, has_attr)| exists && has_attr))
+        Ok(exists? && has_attr.is_ok())
     }
 
-    fn read_block<T>(
+    /// Read a block along with the mode of its header.
+    async fn read_block_with_mode<T>(
         &self,
         path_name: &str,
         data_attrs: &DatasetAttributes,
         grid_position: GridCoord,
-    ) -> Box<dyn Future<Item = Option<VecDataBlock<T>>, Error = Error>>
+    ) -> Result<Option<(VecDataBlock<T>, BlockMode)>, Error>
             where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
                 T: ReflectedType;
 
-    fn list(&self, path_name: &str) -> Box<dyn Future<Item = Vec<String>, Error = Error>>;
+    async fn read_block<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<Option<VecDataBlock<T>>, Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: ReflectedType,
+    {
+        self.read_block_with_mode::<T>(path_name, data_attrs, grid_position).await
+            .map(|maybe_block| maybe_block.map(|(block, _mode)| block))
+    }
+
+    /// Read a block of whatever data type the dataset has, along with the
+    /// mode of its header.
+    async fn read_block_dyn(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<Option<(DynVecDataBlock, BlockMode)>, Error> {
+        let data_type = data_attrs.get_data_type().clone();
+
+        data_type_match! {
+            data_type,
+            self.read_block_with_mode::<RsType>(path_name, data_attrs, grid_position).await
+                .map(|maybe_block| maybe_block.map(|(block, mode)| (DynVecDataBlock::from(block), mode)))
+        }
+    }
 
-    fn list_attributes(&self, path_name: &str) -> Box<dyn Future<Item = serde_json::Value, Error = Error>>;
+    async fn list(&self, path_name: &str) -> Result<Vec<String>, Error>;
+
+    async fn list_attributes(&self, path_name: &str) -> Result<serde_json::Value, Error>;
 }
 
 
+#[async_trait(?Send)]
 pub trait N5AsyncEtagReader {
-    fn block_etag(
+    async fn block_etag(
         &self,
         path_name: &str,
         data_attrs: &DatasetAttributes,
         grid_position: GridCoord,
-    ) -> Box<dyn Future<Item = Option<String>, Error = Error>>;
+    ) -> Result<Option<String>, Error>;
 
-    fn read_block_with_etag<T>(
+    /// Read a block along with the mode of its header and its etag.
+    async fn read_block_with_mode_and_etag<T>(
         &self,
         path_name: &str,
         data_attrs: &DatasetAttributes,
         grid_position: GridCoord,
-    ) -> Box<dyn Future<Item = Option<(VecDataBlock<T>, Option<String>)>, Error = Error>>
+    ) -> Result<Option<(VecDataBlock<T>, BlockMode, Option<String>)>, Error>
             where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
                 T: ReflectedType;
+
+    async fn read_block_with_etag<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<Option<(VecDataBlock<T>, Option<String>)>, Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: ReflectedType,
+    {
+        self.read_block_with_mode_and_etag::<T>(path_name, data_attrs, grid_position).await
+            .map(|maybe_block| maybe_block.map(|(block, _mode, etag)| (block, etag)))
+    }
+
+    /// Read a block, the mode of its header and its etag, of whatever data
+    /// type the dataset has.
+    async fn read_block_with_etag_dyn(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<Option<(DynVecDataBlock, BlockMode, Option<String>)>, Error> {
+        let data_type = data_attrs.get_data_type().clone();
+
+        data_type_match! {
+            data_type,
+            self.read_block_with_mode_and_etag::<RsType>(path_name, data_attrs, grid_position).await
+                .map(|maybe_block| maybe_block.map(|(block, mode, etag)| (DynVecDataBlock::from(block), mode, etag)))
+        }
+    }
+}
+
+
+pub trait N5PromiseWriter {
+    /// Merge the top-level keys of a JSON object into the attributes of a
+    /// group or dataset.
+    fn set_attributes(&self, path_name: &str, attributes: AttributesJson) -> PromiseVoid;
+
+    fn create_group(&self, path_name: &str) -> PromiseVoid;
+
+    fn create_dataset(&self, path_name: &str, data_attrs: &wrapped::DatasetAttributes) -> PromiseVoid;
+
+    fn remove(&self, path_name: &str) -> PromiseVoid;
+
+    /// Write a block from a typed array of the dataset's data type. Blocks
+    /// whose data does not fill the block size are written as varlength.
+    fn write_block(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        grid_position: Coordinates,
+        data: BlockData,
+    ) -> PromiseVoid;
+
+    fn delete_block(&self, path_name: &str, grid_position: Coordinates) -> PromiseBool;
 }
 
+impl<T> N5PromiseWriter for T where T: N5AsyncWriter + Clone + 'static {
+    fn set_attributes(&self, path_name: &str, attributes: AttributesJson) -> PromiseVoid {
+        let writer = self.clone();
+        let path_name = path_name.to_owned();
+        let to_return = async move {
+            let attributes = attributes.into_serde()?;
+            writer.set_attributes(&path_name, attributes).await
+                .map(|_| JsValue::UNDEFINED)
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
+
+    fn create_group(&self, path_name: &str) -> PromiseVoid {
+        let writer = self.clone();
+        let path_name = path_name.to_owned();
+        let to_return = async move {
+            writer.create_group(&path_name).await
+                .map(|_| JsValue::UNDEFINED)
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
+
+    fn create_dataset(&self, path_name: &str, data_attrs: &wrapped::DatasetAttributes) -> PromiseVoid {
+        let writer = self.clone();
+        let path_name = path_name.to_owned();
+        let data_attrs = data_attrs.0.clone();
+        let to_return = async move {
+            writer.create_dataset(&path_name, &data_attrs).await
+                .map(|_| JsValue::UNDEFINED)
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
+
+    fn remove(&self, path_name: &str) -> PromiseVoid {
+        let writer = self.clone();
+        let path_name = path_name.to_owned();
+        let to_return = async move {
+            writer.remove(&path_name).await
+                .map(|_| JsValue::UNDEFINED)
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
+
+    fn write_block(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        grid_position: Coordinates,
+        data: BlockData,
+    ) -> PromiseVoid {
+
+        let writer = self.clone();
+        let path_name = path_name.to_owned();
+        let data_attrs = data_attrs.0.clone();
+        let data_type = data_attrs.get_data_type().clone();
+        let grid_position = grid_position.to_vec();
 
-fn map_future_error_rust<F: Future<Item = T, Error = JsValue>, T>(future: F)
-        -> impl Future<Item = T, Error = Error> {
+        data_type_match! {
+            data_type,
+            future_to_promise(map_future_error_wasm(async move {
+                let grid_position = grid_position?;
+                let block = VecDataBlock::<RsType>::new(
+                    block_size_at(&data_attrs, &grid_position).into(),
+                    grid_position.into(),
+                    <RsType as VecBlockMonomorphizerReflection>::data_from_js(&data));
+                writer.write_block(&path_name, &data_attrs, &block).await
+                    .map(|_| JsValue::UNDEFINED)
+            })).unchecked_into()
+        }
+    }
+
+    fn delete_block(&self, path_name: &str, grid_position: Coordinates) -> PromiseBool {
+        let writer = self.clone();
+        let path_name = path_name.to_owned();
+        let grid_position = grid_position.to_vec();
+        let to_return = async move {
+            writer.delete_block(&path_name, &grid_position?).await
+                .map(JsValue::from)
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
+}
+
+
+/// Async counterpart to `n5::N5Writer`, for backends that support writing.
+#[async_trait(?Send)]
+pub trait N5AsyncWriter: N5AsyncReader {
+    /// Merge the top-level keys of `attributes` into the existing attributes
+    /// of a group or dataset.
+    async fn set_attributes(
+        &self,
+        path_name: &str,
+        attributes: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<(), Error>;
+
+    async fn set_dataset_attributes(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+    ) -> Result<(), Error> {
+        if let serde_json::Value::Object(map) = serde_json::to_value(data_attrs)? {
+            self.set_attributes(path_name, map).await
+        } else {
+            panic!("Impossible: DatasetAttributes serializes to object")
+        }
+    }
+
+    /// Create a group, including any missing parent groups.
+    async fn create_group(&self, path_name: &str) -> Result<(), Error>;
+
+    async fn create_dataset(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+    ) -> Result<(), Error> {
+        self.create_group(path_name).await?;
+        self.set_dataset_attributes(path_name, data_attrs).await
+    }
+
+    /// Remove a group or dataset, including all children and blocks.
+    async fn remove(&self, path_name: &str) -> Result<(), Error>;
+
+    async fn write_block<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        block: &VecDataBlock<T>,
+    ) -> Result<(), Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::WriteableDataBlock,
+                T: ReflectedType;
+
+    /// Delete a block, returning whether it existed.
+    async fn delete_block(
+        &self,
+        path_name: &str,
+        grid_position: &[u64],
+    ) -> Result<bool, Error>;
+}
+
+
+fn map_future_error_rust<F: Future<Output = Result<T, JsValue>>, T>(future: F)
+        -> impl Future<Output = Result<T, Error>> {
     future.map_err(convert_jsvalue_error)
 }
 
-fn map_future_error_wasm<F: Future<Item = T, Error = Error>, T>(future: F)
-        -> impl Future<Item = T, Error = JsValue> {
+fn map_future_error_wasm<F: Future<Output = Result<T, Error>>, T>(future: F)
+        -> impl Future<Output = Result<T, JsValue>> {
     future.map_err(|error| {
         let js_error = js_sys::Error::new(&format!("{:?}", error));
         JsValue::from(js_error)
@@ -221,6 +672,323 @@ fn convert_jsvalue_error(error: JsValue) -> Error {
     Error::new(std::io::ErrorKind::Other, format!("{:?}", error))
 }
 
+/// Path of a block relative to the container root, shared by all backends
+/// so they agree on the N5 directory layout.
+fn relative_block_path(path_name: &str, grid_position: &[u64]) -> String {
+    let mut block_path = path_name.to_owned();
+    for coord in grid_position {
+        write!(block_path, "/{}", coord).unwrap();
+    }
+
+    block_path
+}
+
+/// Size of the block at a grid position, which is truncated for blocks at the
+/// upper boundary of the dataset.
+fn block_size_at(data_attrs: &DatasetAttributes, grid_position: &[u64]) -> Vec<u32> {
+    data_attrs.get_dimensions().iter()
+        .zip(data_attrs.get_block_size())
+        .zip(grid_position)
+        .map(|((&dim, &block), &coord)| {
+            let start = coord * u64::from(block);
+            dim.saturating_sub(start).min(u64::from(block)) as u32
+        })
+        .collect()
+}
+
+/// Grid positions of the blocks intersecting a region of voxels, clamped to
+/// the dataset, in column-major order.
+fn region_grid_positions(
+    data_attrs: &DatasetAttributes,
+    offset: &[u64],
+    size: &[u64],
+) -> Result<Vec<Vec<u64>>, Error> {
+    let ndim = data_attrs.get_ndim();
+    if offset.len() != ndim || size.len() != ndim {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Region must have {} dimensions", ndim)));
+    }
+
+    // Half-open ranges of grid coordinates along each dimension.
+    let mut grid_start = Vec::with_capacity(ndim);
+    let mut grid_end = Vec::with_capacity(ndim);
+    for (((&dim, &block), &start), &extent) in data_attrs.get_dimensions().iter()
+            .zip(data_attrs.get_block_size())
+            .zip(offset)
+            .zip(size) {
+        let end = start.saturating_add(extent).min(dim);
+        if start >= end {
+            return Ok(Vec::new());
+        }
+        grid_start.push(start / u64::from(block));
+        grid_end.push((end - 1) / u64::from(block) + 1);
+    }
+
+    let mut grid_positions = Vec::new();
+    let mut grid_position = grid_start.clone();
+    loop {
+        grid_positions.push(grid_position.clone());
+        if !increment_column_major(&mut grid_position, &grid_start, &grid_end) {
+            return Ok(grid_positions);
+        }
+    }
+}
+
+/// Step coordinates through a half-open box, with the first dimension
+/// fastest. Returns false once every coordinate has been visited.
+fn increment_column_major(coord: &mut [u64], start: &[u64], end: &[u64]) -> bool {
+    for ((c, &s), &e) in coord.iter_mut().zip(start).zip(end) {
+        if *c + 1 < e {
+            *c += 1;
+            return true;
+        }
+        *c = s;
+    }
+
+    false
+}
+
+/// Visit the elements of a block that lie within a region of voxels, passing
+/// each element's coordinates relative to the region's offset.
+fn for_each_in_region<T>(
+    data_attrs: &DatasetAttributes,
+    block: &VecDataBlock<T>,
+    offset: &[u64],
+    size: &[u64],
+    mut visit: impl FnMut(&[u64], &T),
+) where VecDataBlock<T>: DataBlock<T> {
+    let block_size = block.get_size();
+    let block_offset: Vec<u64> = block.get_grid_position().iter()
+        .zip(data_attrs.get_block_size())
+        .map(|(&coord, &block)| coord * u64::from(block))
+        .collect();
+
+    // Half-open range of the intersection, in block-local coordinates.
+    let mut start = Vec::with_capacity(block_size.len());
+    let mut end = Vec::with_capacity(block_size.len());
+    for (((&block_dim, &block_start), &region_start), &region_extent) in block_size.iter()
+            .zip(&block_offset)
+            .zip(offset)
+            .zip(size) {
+        let lower = region_start.max(block_start);
+        let upper = region_start.saturating_add(region_extent)
+            .min(block_start + u64::from(block_dim));
+        if lower >= upper {
+            return;
+        }
+        start.push(lower - block_start);
+        end.push(upper - block_start);
+    }
+
+    let mut strides = Vec::with_capacity(block_size.len());
+    let mut stride = 1;
+    for &block_dim in block_size {
+        strides.push(stride);
+        stride *= u64::from(block_dim);
+    }
+
+    let data = block.get_data();
+    let mut local = start.clone();
+    let mut region_coord = vec![0; local.len()];
+    loop {
+        let mut index = 0;
+        for ((((coord, &l), &stride), &block_start), &region_start) in region_coord.iter_mut()
+                .zip(&local)
+                .zip(&strides)
+                .zip(&block_offset)
+                .zip(offset) {
+            index += l * stride;
+            *coord = block_start + l - region_start;
+        }
+        // Varlength blocks may hold fewer elements than their size.
+        if let Some(value) = data.get(index as usize) {
+            visit(&region_coord, value);
+        }
+
+        if !increment_column_major(&mut local, &start, &end) {
+            return;
+        }
+    }
+}
+
+/// Blocks of a dataset read to look up individual voxels, such as for
+/// sampling or point queries.
+struct VoxelLookup<T> {
+    block_size: Vec<u64>,
+    blocks: std::collections::HashMap<Vec<u64>, VecDataBlock<T>>,
+}
+
+impl<T> VoxelLookup<T>
+        where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+            T: ReflectedType + Copy,
+{
+    /// Read the blocks at some grid positions concurrently. Missing blocks
+    /// are skipped.
+    async fn read<R: N5AsyncReader + ?Sized>(
+        reader: &R,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_positions: impl IntoIterator<Item = Vec<u64>>,
+    ) -> Result<VoxelLookup<T>, Error> {
+        let blocks = futures::future::try_join_all(grid_positions.into_iter()
+            .map(|grid_position| reader.read_block::<T>(path_name, data_attrs, grid_position.into())))
+            .await?;
+
+        Ok(VoxelLookup {
+            block_size: data_attrs.get_block_size().iter().map(|&b| u64::from(b)).collect(),
+            blocks: blocks.into_iter()
+                .flatten()
+                .map(|block| (block.get_grid_position().to_vec(), block))
+                .collect(),
+        })
+    }
+
+    fn grid_position(block_size: &[u64], coord: &[u64]) -> Vec<u64> {
+        coord.iter().zip(block_size).map(|(&c, &b)| c / b).collect()
+    }
+
+    /// The value of a voxel, if its block was read and holds it.
+    fn get(&self, coord: &[u64]) -> Option<T> {
+        let block = self.blocks.get(&Self::grid_position(&self.block_size, coord))?;
+        let mut index = 0;
+        let mut stride = 1;
+        for ((&c, &b), &size) in coord.iter().zip(&self.block_size).zip(block.get_size()) {
+            index += (c % b) * stride;
+            stride *= u64::from(size);
+        }
+
+        block.get_data().get(index as usize).copied()
+    }
+}
+
+/// Numeric data types, converted through `f64` for interpolation,
+/// statistics and rendering.
+pub trait Numeric: ReflectedType + Copy + Default + PartialOrd {
+    fn to_f64(self) -> f64;
+
+    /// Convert from `f64`, rounding and saturating for integer types.
+    fn from_f64(value: f64) -> Self;
+}
+
+macro_rules! numeric_integer {
+    ($($d_type:ty),*) => {
+        $(
+            impl Numeric for $d_type {
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_f64(value: f64) -> Self {
+                    value.round() as $d_type
+                }
+            }
+        )*
+    }
+}
+
+numeric_integer!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl Numeric for f32 {
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+impl Numeric for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+/// Serialize a block in the default N5 block format.
+fn encode_block<T>(
+    data_attrs: &DatasetAttributes,
+    block: &VecDataBlock<T>,
+) -> Result<Vec<u8>, Error>
+        where VecDataBlock<T>: DataBlock<T> + n5::WriteableDataBlock,
+            T: ReflectedType,
+{
+    let mut buffer = Vec::new();
+    <n5::DefaultBlock as n5::DefaultBlockWriter<T, _, _>>::write_block(
+        &mut buffer,
+        data_attrs,
+        block)?;
+
+    Ok(buffer)
+}
+
+/// Merge the top-level keys of `attributes` into an existing attributes object.
+fn merge_attributes(
+    existing: &mut serde_json::Map<String, serde_json::Value>,
+    attributes: serde_json::Map<String, serde_json::Value>,
+) {
+    for (key, value) in attributes {
+        existing.insert(key, value);
+    }
+}
+
+fn get_dataset_attributes_path(path_name: &str) -> String {
+    if path_name.is_empty() {
+        ATTRIBUTES_FILE.to_owned()
+    } else {
+        format!("{}/{}", path_name, ATTRIBUTES_FILE)
+    }
+}
+
+/// Percent-encode everything but unreserved characters, as URLs for object
+/// stores and SigV4 signing require.
+fn uri_encode(value: &str, encode_slash: bool) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' =>
+                encoded.push(byte as char),
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => write!(encoded, "%{:02X}", byte).unwrap(),
+        }
+    }
+
+    encoded
+}
+
+/// Contents of each `<tag>` element, for the flat XML listings of object
+/// stores.
+fn xml_elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let mut elements = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let content = &rest[start + open.len()..];
+        match content.find(&close) {
+            Some(end) => {
+                elements.push(&content[..end]);
+                rest = &content[end + close.len()..];
+            },
+            None => break,
+        }
+    }
+
+    elements
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
 
 pub mod wrapped {
     use super::*;
@@ -271,77 +1039,265 @@ pub mod wrapped {
             self.0.get_block_num_elements()
         }
 
-        pub fn to_json(&self) -> JsValue {
-            JsValue::from_serde(self).unwrap()
+        pub fn to_json(&self) -> DatasetAttributesJson {
+            JsValue::from_serde(self).unwrap().unchecked_into()
         }
 
-        pub fn from_json(js: &JsValue) -> Self {
+        pub fn from_json(js: &DatasetAttributesJson) -> Self {
             JsValue::into_serde(js).unwrap()
         }
     }
 }
 
-trait VecBlockMonomorphizerReflection {
-    type MONOMORPH;
+/// Block header mode, as in the N5 specification.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlockMode {
+    Default = 0,
+    /// The header carries the number of elements, which may differ from the
+    /// product of the block size, as for label lists and other ragged data.
+    Varlength = 1,
 }
 
-macro_rules! data_block_monomorphizer {
-    ($d_name:ident, $d_type:ty) => {
-        #[wasm_bindgen]
-        pub struct $d_name(VecDataBlock<$d_type>, Option<String>);
+impl BlockMode {
+    /// Mode of a serialized block, from the big endian `u16` starting its
+    /// header.
+    pub fn from_header(buff: &[u8]) -> Result<BlockMode, Error> {
+        match buff {
+            [0, 0, ..] => Ok(BlockMode::Default),
+            [0, 1, ..] => Ok(BlockMode::Varlength),
+            [high, low, ..] => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Unsupported block mode: {}", u16::from_be_bytes([*high, *low])))),
+            _ => Err(Error::new(ErrorKind::UnexpectedEof, "Block header is truncated")),
+        }
+    }
+}
+
+/// Deserialize a block in the default N5 block format, along with the mode
+/// of its header. Blocks are written in varlength mode only if their number
+/// of elements differs from the product of their size, but other writers may
+/// use it for any block.
+pub fn decode_block<T>(
+    buff: &[u8],
+    data_attrs: &DatasetAttributes,
+    grid_position: GridCoord,
+) -> Result<(VecDataBlock<T>, BlockMode), Error>
+        where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+            T: ReflectedType,
+{
+    let mode = BlockMode::from_header(buff)?;
+    let block = <n5::DefaultBlock as n5::DefaultBlockReader<T, &[u8]>>::read_block(
+        buff,
+        data_attrs,
+        grid_position)?;
+
+    Ok((block, mode))
+}
+
+trait VecBlockMonomorphizerReflection: Sized {
+    /// Copy block data out of a JS array or typed array.
+    fn data_from_js(value: &JsValue) -> Vec<Self>;
 
+    /// Copy block data into a typed array of this type.
+    fn data_to_js(data: &[Self]) -> JsValue;
+}
+
+macro_rules! data_block_monomorphizer {
+    ($d_type:ty, $js_array:ty) => {
         impl VecBlockMonomorphizerReflection for $d_type {
-            type MONOMORPH = $d_name;
-        }
+            fn data_from_js(value: &JsValue) -> Vec<Self> {
+                <$js_array>::new(value).to_vec()
+            }
 
-        impl From<VecDataBlock<$d_type>> for $d_name {
-            fn from(block: VecDataBlock<$d_type>) -> Self {
-                $d_name(block, None)
+            fn data_to_js(data: &[Self]) -> JsValue {
+                <$js_array>::from(data).into()
             }
         }
+    }
+}
 
-        impl From<(VecDataBlock<$d_type>, Option<String>)> for $d_name {
-            fn from((block, etag): (VecDataBlock<$d_type>, Option<String>)) -> Self {
-                $d_name(block, etag)
-            }
+data_block_monomorphizer!(u8,  js_sys::Uint8Array);
+data_block_monomorphizer!(u16, js_sys::Uint16Array);
+data_block_monomorphizer!(u32, js_sys::Uint32Array);
+data_block_monomorphizer!(u64, js_sys::BigUint64Array);
+data_block_monomorphizer!(i8,  js_sys::Int8Array);
+data_block_monomorphizer!(i16, js_sys::Int16Array);
+data_block_monomorphizer!(i32, js_sys::Int32Array);
+data_block_monomorphizer!(i64, js_sys::BigInt64Array);
+data_block_monomorphizer!(f32, js_sys::Float32Array);
+data_block_monomorphizer!(f64, js_sys::Float64Array);
+
+/// Generate `DynVecDataBlock` over every data type.
+macro_rules! dyn_vec_data_block {
+    ($($variant:ident: $d_type:ty;)*) => {
+        /// A block of any data type, for consumers that only know the data
+        /// type of a dataset at run time.
+        pub enum DynVecDataBlock {
+            $($variant(VecDataBlock<$d_type>),)*
         }
 
-        #[wasm_bindgen]
-        impl $d_name {
-            pub fn get_size(&self) -> Vec<u32> {
-                self.0.get_size().to_owned()
+        $(
+            impl From<VecDataBlock<$d_type>> for DynVecDataBlock {
+                fn from(block: VecDataBlock<$d_type>) -> Self {
+                    DynVecDataBlock::$variant(block)
+                }
             }
+        )*
 
-            pub fn get_grid_position(&self) -> Vec<u64> {
-                self.0.get_grid_position().to_owned()
+        impl DynVecDataBlock {
+            pub fn get_data_type(&self) -> DataType {
+                match self {
+                    $(DynVecDataBlock::$variant(_) => DataType::$variant,)*
+                }
             }
 
-            pub fn get_data(&self) -> Vec<$d_type> {
-                self.0.get_data().to_owned()
+            pub fn get_size(&self) -> &[u32] {
+                match self {
+                    $(DynVecDataBlock::$variant(block) => block.get_size(),)*
+                }
             }
 
-            pub fn into_data(self) -> Vec<$d_type> {
-                self.0.into_data()
+            pub fn get_grid_position(&self) -> &[u64] {
+                match self {
+                    $(DynVecDataBlock::$variant(block) => block.get_grid_position(),)*
+                }
             }
 
             pub fn get_num_elements(&self) -> u32 {
-                self.0.get_num_elements()
+                match self {
+                    $(DynVecDataBlock::$variant(block) => block.get_num_elements(),)*
+                }
+            }
+
+            /// Copy the data into a typed array of the block's data type.
+            fn data_to_js(&self) -> JsValue {
+                match self {
+                    $(DynVecDataBlock::$variant(block) =>
+                        <$d_type as VecBlockMonomorphizerReflection>::data_to_js(block.get_data()),)*
+                }
             }
 
-            pub fn get_etag(&self) -> Option<String> {
-                self.1.to_owned()
+            /// Wrap as the JS `DataBlock` class.
+            fn into_js(self, mode: BlockMode, etag: Option<String>) -> JsValue {
+                JsValue::from(JsDataBlock { block: self, mode, etag })
             }
         }
     }
 }
 
-data_block_monomorphizer!(VecDataBlockUINT8,  u8);
-data_block_monomorphizer!(VecDataBlockUINT16, u16);
-data_block_monomorphizer!(VecDataBlockUINT32, u32);
-data_block_monomorphizer!(VecDataBlockUINT64, u64);
-data_block_monomorphizer!(VecDataBlockINT8,  i8);
-data_block_monomorphizer!(VecDataBlockINT16, i16);
-data_block_monomorphizer!(VecDataBlockINT32, i32);
-data_block_monomorphizer!(VecDataBlockINT64, i64);
-data_block_monomorphizer!(VecDataBlockFLOAT32, f32);
-data_block_monomorphizer!(VecDataBlockFLOAT64, f64);
+dyn_vec_data_block! {
+    UINT8: u8;
+    UINT16: u16;
+    UINT32: u32;
+    UINT64: u64;
+    INT8: i8;
+    INT16: i16;
+    INT32: i32;
+    INT64: i64;
+    FLOAT32: f32;
+    FLOAT64: f64;
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const DATA_BLOCK_TYPES: &str = r#"
+/** Names of N5 data types, as in dataset attributes. */
+export type DataType =
+  | "uint8" | "uint16" | "uint32" | "uint64"
+  | "int8" | "int16" | "int32" | "int64"
+  | "float32" | "float64";
+
+/** The typed array holding data of each data type. */
+export type TypedArrayFor<D extends DataType> =
+  D extends "uint8" ? Uint8Array :
+  D extends "uint16" ? Uint16Array :
+  D extends "uint32" ? Uint32Array :
+  D extends "uint64" ? BigUint64Array :
+  D extends "int8" ? Int8Array :
+  D extends "int16" ? Int16Array :
+  D extends "int32" ? Int32Array :
+  D extends "int64" ? BigInt64Array :
+  D extends "float32" ? Float32Array :
+  D extends "float64" ? Float64Array :
+  never;
+
+/** A `DataBlock` narrowed to one data type by checking `dtype`. */
+export type TypedDataBlock<D extends DataType> = DataBlock & {
+  readonly dtype: D;
+  readonly data: TypedArrayFor<D>;
+};
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "DataType")]
+    pub type DataTypeName;
+
+    #[wasm_bindgen(typescript_type = "TypedArrayFor<DataType>")]
+    pub type BlockData;
+}
+
+/// A block of any data type, as exposed to JS.
+#[wasm_bindgen(js_name = DataBlock)]
+pub struct JsDataBlock {
+    block: DynVecDataBlock,
+    mode: BlockMode,
+    etag: Option<String>,
+}
+
+impl JsDataBlock {
+    pub fn block(&self) -> &DynVecDataBlock {
+        &self.block
+    }
+
+    pub fn get_mode(&self) -> BlockMode {
+        self.mode
+    }
+
+    pub fn into_block(self) -> DynVecDataBlock {
+        self.block
+    }
+}
+
+#[wasm_bindgen(js_class = DataBlock)]
+impl JsDataBlock {
+    /// Data type name, such as `"uint8"`.
+    #[wasm_bindgen(getter)]
+    pub fn dtype(&self) -> DataTypeName {
+        JsValue::from(self.block.get_data_type().to_string()).unchecked_into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> Vec<u32> {
+        self.block.get_size().to_owned()
+    }
+
+    #[wasm_bindgen(getter, js_name = gridPosition)]
+    pub fn grid_position(&self) -> Vec<u64> {
+        self.block.get_grid_position().to_owned()
+    }
+
+    /// Number of elements in the data, which for varlength blocks may differ
+    /// from the product of `size`.
+    #[wasm_bindgen(getter, js_name = numElements)]
+    pub fn num_elements(&self) -> u32 {
+        self.block.get_num_elements()
+    }
+
+    /// Mode of the block's header.
+    #[wasm_bindgen(getter)]
+    pub fn mode(&self) -> BlockMode {
+        self.mode
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn etag(&self) -> Option<String> {
+        self.etag.clone()
+    }
+
+    /// A copy of the data, in the typed array for `dtype`.
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> BlockData {
+        self.block.data_to_js().unchecked_into()
+    }
+}
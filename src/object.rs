@@ -0,0 +1,267 @@
+//! Datasets of opaque bytes, the Java N5 `object` data type, for storing
+//! per-block metadata and serialized structures alongside numeric datasets.
+//!
+//! Object blocks have a mode 2 header: the mode as a big endian `u16`
+//! followed by the number of bytes as a big endian `i32`, followed by the
+//! bytes compressed with the dataset's compression.
+
+use std::io::{
+    Read,
+    Write,
+};
+
+use n5::compression::{
+    Compression,
+    CompressionType,
+};
+
+use super::*;
+
+
+const OBJECT_DATA_TYPE: &str = "object";
+const OBJECT_BLOCK_MODE: u16 = 2;
+const OBJECT_HEADER_SIZE: usize = 6;
+
+
+/// Attributes of an object dataset, which cannot be represented by
+/// `n5::DatasetAttributes` since it has no object data type.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectDatasetAttributes {
+    dimensions: Vec<u64>,
+    block_size: Vec<u32>,
+    data_type: String,
+    compression: CompressionType,
+}
+
+impl ObjectDatasetAttributes {
+    pub fn new(
+        dimensions: Vec<u64>,
+        block_size: Vec<u32>,
+        compression: CompressionType,
+    ) -> ObjectDatasetAttributes {
+        ObjectDatasetAttributes {
+            dimensions,
+            block_size,
+            data_type: OBJECT_DATA_TYPE.to_owned(),
+            compression,
+        }
+    }
+
+    /// Parse object dataset attributes from a group's attributes, failing if
+    /// the group is not an object dataset.
+    pub fn from_attributes(attributes: serde_json::Value) -> Result<ObjectDatasetAttributes, Error> {
+        let data_attrs: ObjectDatasetAttributes = serde_json::from_value(attributes)?;
+        if data_attrs.data_type != OBJECT_DATA_TYPE {
+            return Err(Error::new(ErrorKind::InvalidData, "Not an object dataset"));
+        }
+
+        Ok(data_attrs)
+    }
+
+    pub fn get_dimensions(&self) -> &[u64] {
+        &self.dimensions
+    }
+
+    pub fn get_block_size(&self) -> &[u32] {
+        &self.block_size
+    }
+
+    pub fn get_compression(&self) -> &CompressionType {
+        &self.compression
+    }
+}
+
+/// Decode the bytes of a serialized object block.
+pub fn decode_object_block(
+    data_attrs: &ObjectDatasetAttributes,
+    buff: &[u8],
+) -> Result<Vec<u8>, Error> {
+    if buff.len() < OBJECT_HEADER_SIZE {
+        return Err(Error::new(ErrorKind::UnexpectedEof, "Object block header is truncated"));
+    }
+    let mode = u16::from_be_bytes([buff[0], buff[1]]);
+    if mode != OBJECT_BLOCK_MODE {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("Expected object block mode, found mode {}", mode)));
+    }
+    let num_bytes = i32::from_be_bytes([buff[2], buff[3], buff[4], buff[5]]);
+    if num_bytes < 0 {
+        return Err(Error::new(ErrorKind::InvalidData, "Negative object block length"));
+    }
+
+    // The length is not trusted, so preallocate at most the size of the block.
+    let mut data = Vec::with_capacity((num_bytes as usize).min(buff.len() - OBJECT_HEADER_SIZE));
+    data_attrs.compression.decoder(&buff[OBJECT_HEADER_SIZE..])
+        .take(num_bytes as u64)
+        .read_to_end(&mut data)?;
+    if data.len() != num_bytes as usize {
+        return Err(Error::new(ErrorKind::UnexpectedEof, "Object block data is truncated"));
+    }
+
+    Ok(data)
+}
+
+/// Serialize an object block.
+pub fn encode_object_block(
+    data_attrs: &ObjectDatasetAttributes,
+    data: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let mut buffer = Vec::with_capacity(OBJECT_HEADER_SIZE + data.len());
+    buffer.extend_from_slice(&OBJECT_BLOCK_MODE.to_be_bytes());
+    buffer.extend_from_slice(&(data.len() as i32).to_be_bytes());
+    {
+        let mut encoder = data_attrs.compression.encoder(&mut buffer);
+        encoder.write_all(data)?;
+    }
+
+    Ok(buffer)
+}
+
+
+/// Reading object datasets, for backends that can read serialized blocks.
+#[async_trait(?Send)]
+pub trait N5AsyncObjectReader: N5AsyncReader {
+    /// Read the serialized bytes of a block, whatever its data type.
+    async fn read_block_bytes(
+        &self,
+        path_name: &str,
+        grid_position: &[u64],
+    ) -> Result<Option<Vec<u8>>, Error>;
+
+    async fn get_object_dataset_attributes(
+        &self,
+        path_name: &str,
+    ) -> Result<ObjectDatasetAttributes, Error> {
+        ObjectDatasetAttributes::from_attributes(self.list_attributes(path_name).await?)
+    }
+
+    async fn read_object_block(
+        &self,
+        path_name: &str,
+        data_attrs: &ObjectDatasetAttributes,
+        grid_position: &[u64],
+    ) -> Result<Option<Vec<u8>>, Error> {
+        match self.read_block_bytes(path_name, grid_position).await? {
+            Some(buff) => Ok(Some(decode_object_block(data_attrs, &buff)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Writing object datasets, for backends that can write serialized blocks.
+#[async_trait(?Send)]
+pub trait N5AsyncObjectWriter: N5AsyncWriter + N5AsyncObjectReader {
+    /// Write the serialized bytes of a block, whatever its data type.
+    async fn write_block_bytes(
+        &self,
+        path_name: &str,
+        grid_position: &[u64],
+        bytes: Vec<u8>,
+    ) -> Result<(), Error>;
+
+    async fn create_object_dataset(
+        &self,
+        path_name: &str,
+        data_attrs: &ObjectDatasetAttributes,
+    ) -> Result<(), Error> {
+        self.create_group(path_name).await?;
+        if let serde_json::Value::Object(map) = serde_json::to_value(data_attrs)? {
+            self.set_attributes(path_name, map).await
+        } else {
+            panic!("Impossible: ObjectDatasetAttributes serializes to object")
+        }
+    }
+
+    async fn write_object_block(
+        &self,
+        path_name: &str,
+        data_attrs: &ObjectDatasetAttributes,
+        grid_position: &[u64],
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let bytes = encode_object_block(data_attrs, data)?;
+        self.write_block_bytes(path_name, grid_position, bytes).await
+    }
+}
+
+
+pub trait N5PromiseObjectReader {
+    /// Resolve to the attributes of an object dataset as a JSON object.
+    fn get_object_dataset_attributes(&self, path_name: &str) -> PromiseObjectDatasetAttributes;
+
+    /// Resolve to a block's bytes as a `Uint8Array`, or `undefined` if the
+    /// block does not exist.
+    fn read_object_block(&self, path_name: &str, grid_position: Coordinates) -> PromiseBytes;
+}
+
+impl<T> N5PromiseObjectReader for T where T: N5AsyncObjectReader + Clone + 'static {
+    fn get_object_dataset_attributes(&self, path_name: &str) -> PromiseObjectDatasetAttributes {
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let to_return = async move {
+            reader.get_object_dataset_attributes(&path_name).await
+                .map(|data_attrs| JsValue::from_serde(&data_attrs).unwrap())
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
+
+    fn read_object_block(&self, path_name: &str, grid_position: Coordinates) -> PromiseBytes {
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let grid_position = grid_position.to_vec();
+        let to_return = async move {
+            let grid_position = grid_position?;
+            let data_attrs = reader.get_object_dataset_attributes(&path_name).await?;
+            reader.read_object_block(&path_name, &data_attrs, &grid_position).await
+                .map(|maybe_data| maybe_data
+                    .map(|data| JsValue::from(js_sys::Uint8Array::from(&data[..])))
+                    .unwrap_or(JsValue::UNDEFINED))
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
+}
+
+pub trait N5PromiseObjectWriter {
+    /// Create an object dataset from attributes with `dimensions`,
+    /// `blockSize` and `compression` keys.
+    fn create_object_dataset(&self, path_name: &str, data_attrs: AttributesJson) -> PromiseVoid;
+
+    /// Write a block from a `Uint8Array`.
+    fn write_object_block(&self, path_name: &str, grid_position: Coordinates, data: Vec<u8>) -> PromiseVoid;
+}
+
+impl<T> N5PromiseObjectWriter for T where T: N5AsyncObjectWriter + Clone + 'static {
+    fn create_object_dataset(&self, path_name: &str, data_attrs: AttributesJson) -> PromiseVoid {
+        let writer = self.clone();
+        let path_name = path_name.to_owned();
+        let to_return = async move {
+            let mut attributes: serde_json::Value = data_attrs.into_serde()?;
+            if let serde_json::Value::Object(map) = &mut attributes {
+                map.insert("dataType".to_owned(), OBJECT_DATA_TYPE.into());
+            }
+            let data_attrs = ObjectDatasetAttributes::from_attributes(attributes)?;
+            writer.create_object_dataset(&path_name, &data_attrs).await
+                .map(|_| JsValue::UNDEFINED)
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
+
+    fn write_object_block(&self, path_name: &str, grid_position: Coordinates, data: Vec<u8>) -> PromiseVoid {
+        let writer = self.clone();
+        let path_name = path_name.to_owned();
+        let grid_position = grid_position.to_vec();
+        let to_return = async move {
+            let grid_position = grid_position?;
+            let data_attrs = writer.get_object_dataset_attributes(&path_name).await?;
+            writer.write_object_block(&path_name, &data_attrs, &grid_position, &data).await
+                .map(|_| JsValue::UNDEFINED)
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
+}
@@ -0,0 +1,517 @@
+//! Composition of several containers into one read-only container, such as a
+//! user's local edits over a read-only base segmentation.
+//!
+//! Layers are stacked in the order they are pushed, so later layers are
+//! upper layers. Blocks from upper layers shadow those below, and upper
+//! layers' attributes override the same keys below. A layer may also hold
+//! tombstones for blocks, which mask out that block in all lower layers.
+//! Tombstones are stored in the layer's own container, as the grid positions
+//! listed in the `overlayTombstones` attribute of the dataset, so they are
+//! saved and reloaded along with the layer's edits.
+
+use std::cell::RefCell;
+use std::collections::{
+    BTreeSet,
+    HashMap,
+    HashSet,
+};
+use std::rc::Rc;
+use std::str::FromStr;
+
+use super::*;
+use crate::file_system::N5FileSystem;
+use crate::http_fetch::N5HTTPFetch;
+use crate::in_memory::N5InMemory;
+use crate::object::{
+    N5AsyncObjectReader,
+    N5PromiseObjectReader,
+};
+use crate::s3::N5S3;
+use crate::zip::N5Zip;
+
+
+/// A reader of any backend that can be stacked in an overlay.
+#[derive(Clone)]
+pub enum Layer {
+    FileSystem(N5FileSystem),
+    Http(N5HTTPFetch),
+    Memory(N5InMemory),
+    S3(N5S3),
+    Zip(N5Zip),
+}
+
+/// Evaluate an expression with `$reader` bound to the backend of a layer.
+/// Trait methods must be called by UFCS, since the backends' inherent
+/// promise methods shadow them.
+macro_rules! layer_dispatch {
+    ($layer:expr, $reader:ident => $body:expr) => {
+        match $layer {
+            Layer::FileSystem($reader) => $body,
+            Layer::Http($reader) => $body,
+            Layer::Memory($reader) => $body,
+            Layer::S3($reader) => $body,
+            Layer::Zip($reader) => $body,
+        }
+    }
+}
+
+/// Where a block of an overlay comes from.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum BlockProvenance {
+    /// The block is read from this layer.
+    Layer { layer: usize },
+    /// The block is masked by a tombstone in this layer.
+    Tombstone { layer: usize },
+    /// No layer has the block.
+    Missing,
+}
+
+impl Layer {
+    /// Merge attributes into a group of the layer, if its backend is
+    /// writable.
+    async fn set_attributes(
+        &self,
+        path_name: &str,
+        attributes: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<(), Error> {
+        match self {
+            Layer::FileSystem(writer) => N5AsyncWriter::set_attributes(writer, path_name, attributes).await,
+            Layer::Memory(writer) => N5AsyncWriter::set_attributes(writer, path_name, attributes).await,
+            Layer::S3(writer) => N5AsyncWriter::set_attributes(writer, path_name, attributes).await,
+            Layer::Http(_) | Layer::Zip(_) => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tombstones can only be added to writable layers")),
+        }
+    }
+}
+
+/// Dataset attribute listing the grid positions of a layer's tombstones.
+const TOMBSTONES_KEY: &str = "overlayTombstones";
+
+#[derive(Clone)]
+struct OverlayLayer {
+    reader: Layer,
+    /// Tombstones of each dataset of this layer, loaded from the layer on
+    /// first use. Shared between clones.
+    tombstones: Rc<RefCell<HashMap<String, HashSet<Vec<u64>>>>>,
+}
+
+impl OverlayLayer {
+    /// The tombstones of a dataset in this layer. Layers without the
+    /// dataset's attributes have none, but any other failure to read them is
+    /// an error and is not cached, so that tombstones are never overwritten
+    /// from an incomplete set.
+    async fn dataset_tombstones(&self, path_name: &str) -> Result<HashSet<Vec<u64>>, Error> {
+        let path_name = path_name.trim_matches('/');
+        if let Some(tombstones) = self.tombstones.borrow().get(path_name) {
+            return Ok(tombstones.clone());
+        }
+
+        let attributes = layer_dispatch!(&self.reader, reader =>
+            N5AsyncReader::list_attributes(reader, path_name).await);
+        let tombstones: HashSet<Vec<u64>> = match attributes {
+            Ok(attributes) => match attributes.get(TOMBSTONES_KEY) {
+                Some(tombstones) => serde_json::from_value::<Vec<Vec<u64>>>(tombstones.clone())?
+                    .into_iter()
+                    .collect(),
+                None => HashSet::new(),
+            },
+            Err(ref error) if error.kind() == ErrorKind::NotFound => HashSet::new(),
+            Err(error) => return Err(error),
+        };
+        self.tombstones.borrow_mut().insert(path_name.to_owned(), tombstones.clone());
+
+        Ok(tombstones)
+    }
+
+    /// Write the tombstones of a dataset to this layer.
+    async fn set_dataset_tombstones(
+        &self,
+        path_name: &str,
+        tombstones: HashSet<Vec<u64>>,
+    ) -> Result<(), Error> {
+        let path_name = path_name.trim_matches('/');
+        let mut sorted: Vec<&Vec<u64>> = tombstones.iter().collect();
+        sorted.sort();
+        let mut attributes = serde_json::Map::new();
+        attributes.insert(TOMBSTONES_KEY.to_owned(), serde_json::json!(sorted));
+        self.reader.set_attributes(path_name, attributes).await?;
+
+        self.tombstones.borrow_mut().insert(path_name.to_owned(), tombstones);
+
+        Ok(())
+    }
+}
+
+
+/// Read-only container stacking several layers. Clones share the same
+/// layers and tombstones.
+#[wasm_bindgen]
+#[derive(Clone, Default)]
+pub struct N5Overlay {
+    layers: Rc<RefCell<Vec<OverlayLayer>>>,
+}
+
+impl N5Overlay {
+    /// Push a layer on top of the stack, returning its index.
+    pub fn push(&self, reader: Layer) -> usize {
+        let mut layers = self.layers.borrow_mut();
+        layers.push(OverlayLayer {
+            reader,
+            tombstones: Rc::new(RefCell::new(HashMap::new())),
+        });
+
+        layers.len() - 1
+    }
+
+    /// Snapshot of the layers, so that no borrow is held across awaits.
+    fn layers(&self) -> Vec<OverlayLayer> {
+        self.layers.borrow().clone()
+    }
+
+    fn layer(&self, layer: usize) -> Result<OverlayLayer, Error> {
+        self.layers.borrow().get(layer)
+            .cloned()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "No such layer"))
+    }
+
+    /// Mask a block in all layers below `layer`, writing the tombstone to
+    /// that layer's container, which must be writable. Returns whether it
+    /// was not already masked by this layer.
+    pub async fn add_tombstone(
+        &self,
+        layer: usize,
+        path_name: &str,
+        grid_position: &[u64],
+    ) -> Result<bool, Error> {
+        let layer = self.layer(layer)?;
+        let mut tombstones = layer.dataset_tombstones(path_name).await?;
+        if !tombstones.insert(grid_position.to_vec()) {
+            return Ok(false);
+        }
+        layer.set_dataset_tombstones(path_name, tombstones).await?;
+
+        Ok(true)
+    }
+
+    /// Unmask a block, removing the tombstone from the layer's container.
+    /// Returns whether it was masked by this layer.
+    pub async fn remove_tombstone(
+        &self,
+        layer: usize,
+        path_name: &str,
+        grid_position: &[u64],
+    ) -> Result<bool, Error> {
+        let layer = self.layer(layer)?;
+        let mut tombstones = layer.dataset_tombstones(path_name).await?;
+        if !tombstones.remove(grid_position) {
+            return Ok(false);
+        }
+        layer.set_dataset_tombstones(path_name, tombstones).await?;
+
+        Ok(true)
+    }
+
+    /// Read a block along with the layer it was read from, or the layer
+    /// whose tombstone masks it.
+    pub async fn read_block_with_provenance<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<(Option<VecDataBlock<T>>, BlockProvenance), Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: ReflectedType,
+    {
+        self.read_block_with_mode_and_provenance(path_name, data_attrs, grid_position).await
+            .map(|(block, provenance)| (block.map(|(block, _mode)| block), provenance))
+    }
+
+    async fn read_block_with_mode_and_provenance<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<(Option<(VecDataBlock<T>, BlockMode)>, BlockProvenance), Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: ReflectedType,
+    {
+        for (index, layer) in self.layers().iter().enumerate().rev() {
+            if layer.dataset_tombstones(path_name).await?.contains(&grid_position[..]) {
+                return Ok((None, BlockProvenance::Tombstone { layer: index }));
+            }
+
+            let block = layer_dispatch!(&layer.reader, reader =>
+                N5AsyncReader::read_block_with_mode::<T>(reader, path_name, data_attrs, grid_position.clone()).await?);
+            if block.is_some() {
+                return Ok((block, BlockProvenance::Layer { layer: index }));
+            }
+        }
+
+        Ok((None, BlockProvenance::Missing))
+    }
+
+    /// Attributes merged from all layers in which they can be read, with
+    /// upper layers taking precedence.
+    async fn merged_attributes(
+        &self,
+        path_name: &str,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, Error> {
+        let mut merged = None;
+        for layer in self.layers() {
+            let attributes = layer_dispatch!(&layer.reader, reader =>
+                N5AsyncReader::list_attributes(reader, path_name).await);
+            if let Ok(serde_json::Value::Object(attributes)) = attributes {
+                merge_attributes(merged.get_or_insert_with(serde_json::Map::new), attributes);
+            }
+        }
+
+        merged.ok_or_else(|| Error::new(ErrorKind::NotFound, "No layer has attributes"))
+    }
+}
+
+#[wasm_bindgen]
+impl N5Overlay {
+    /// Create an overlay without layers.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> N5Overlay {
+        N5Overlay::default()
+    }
+
+    pub fn push_file_system(&self, reader: &N5FileSystem) -> usize {
+        self.push(Layer::FileSystem(reader.clone()))
+    }
+
+    pub fn push_http(&self, reader: &N5HTTPFetch) -> usize {
+        self.push(Layer::Http(reader.clone()))
+    }
+
+    pub fn push_memory(&self, reader: &N5InMemory) -> usize {
+        self.push(Layer::Memory(reader.clone()))
+    }
+
+    pub fn push_s3(&self, reader: &N5S3) -> usize {
+        self.push(Layer::S3(reader.clone()))
+    }
+
+    pub fn push_zip(&self, reader: &N5Zip) -> usize {
+        self.push(Layer::Zip(reader.clone()))
+    }
+
+    pub fn get_num_layers(&self) -> usize {
+        self.layers.borrow().len()
+    }
+
+    /// Resolve to whether a block was newly masked by a tombstone written
+    /// to a writable layer.
+    pub fn tombstone_block(
+        &self,
+        layer: usize,
+        path_name: &str,
+        grid_position: Coordinates,
+    ) -> PromiseBool {
+        let overlay = self.clone();
+        let path_name = path_name.to_owned();
+        let grid_position = grid_position.to_vec();
+        let to_return = async move {
+            overlay.add_tombstone(layer, &path_name, &grid_position?).await
+                .map(JsValue::from)
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
+
+    /// Resolve to whether a block's tombstone was removed from a layer.
+    pub fn clear_tombstone(
+        &self,
+        layer: usize,
+        path_name: &str,
+        grid_position: Coordinates,
+    ) -> PromiseBool {
+        let overlay = self.clone();
+        let path_name = path_name.to_owned();
+        let grid_position = grid_position.to_vec();
+        let to_return = async move {
+            overlay.remove_tombstone(layer, &path_name, &grid_position?).await
+                .map(JsValue::from)
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
+
+    /// Resolve to `{ kind: "layer", layer }`, `{ kind: "tombstone", layer }`
+    /// or `{ kind: "missing" }`.
+    pub fn block_provenance(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        grid_position: Coordinates,
+    ) -> PromiseBlockProvenance {
+        let overlay = self.clone();
+        let path_name = path_name.to_owned();
+        let data_attrs = data_attrs.0.clone();
+        let data_type = data_attrs.get_data_type().clone();
+        let grid_position = grid_position.to_vec();
+
+        data_type_match! {
+            data_type,
+            future_to_promise(map_future_error_wasm(async move {
+                overlay.read_block_with_provenance::<RsType>(&path_name, &data_attrs, grid_position?.into()).await
+                    .map(|(_block, provenance)| JsValue::from_serde(&provenance).unwrap())
+            })).unchecked_into()
+        }
+    }
+}
+
+/// Delegations to expose N5PromiseReader and N5PromiseObjectReader traits to
+/// WASM.
+#[wasm_bindgen]
+impl N5Overlay {
+    pub fn get_version(&self) -> PromiseVersion {
+        N5PromiseReader::get_version(self)
+    }
+
+    pub fn get_dataset_attributes(&self, path_name: &str) -> PromiseDatasetAttributes {
+        N5PromiseReader::get_dataset_attributes(self, path_name)
+    }
+
+    pub fn exists(&self, path_name: &str) -> PromiseBool {
+        N5PromiseReader::exists(self, path_name)
+    }
+
+    pub fn dataset_exists(&self, path_name: &str) -> PromiseBool {
+        N5PromiseReader::dataset_exists(self, path_name)
+    }
+
+    pub fn read_block(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        grid_position: Coordinates,
+    ) -> PromiseDataBlock {
+        N5PromiseReader::read_block(self, path_name, data_attrs, grid_position)
+    }
+
+    pub fn iter_blocks(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        offset: Coordinates,
+        size: Coordinates,
+    ) -> Result<DataBlockAsyncIterator, JsValue> {
+        N5PromiseReader::iter_blocks(self, path_name, data_attrs, offset, size)
+    }
+
+    pub fn list(&self, path_name: &str) -> PromiseStrings {
+        N5PromiseReader::list(self, path_name)
+    }
+
+    pub fn list_attributes(&self, path_name: &str) -> PromiseAttributes {
+        N5PromiseReader::list_attributes(self, path_name)
+    }
+
+    pub fn get_object_dataset_attributes(&self, path_name: &str) -> PromiseObjectDatasetAttributes {
+        N5PromiseObjectReader::get_object_dataset_attributes(self, path_name)
+    }
+
+    pub fn read_object_block(&self, path_name: &str, grid_position: Coordinates) -> PromiseBytes {
+        N5PromiseObjectReader::read_object_block(self, path_name, grid_position)
+    }
+}
+
+region_reader_delegations!(N5Overlay);
+
+#[async_trait(?Send)]
+impl N5AsyncReader for N5Overlay {
+    async fn get_version(&self) -> Result<n5::Version, Error> {
+        let attr = self.merged_attributes("").await?;
+        let ver = attr.get(n5::VERSION_ATTRIBUTE_KEY)
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "Not an N5 root"))?;
+
+        n5::Version::from_str(ver.as_str().unwrap_or(""))
+            .map_err(|error| Error::new(ErrorKind::InvalidData, format!("Invalid N5 version: {}", error)))
+    }
+
+    async fn get_dataset_attributes(&self, path_name: &str) -> Result<n5::DatasetAttributes, Error> {
+        let attr = self.merged_attributes(path_name).await?;
+
+        Ok(serde_json::from_value(serde_json::Value::Object(attr))?)
+    }
+
+    async fn exists(&self, path_name: &str) -> Result<bool, Error> {
+        for layer in self.layers() {
+            if layer_dispatch!(&layer.reader, reader => N5AsyncReader::exists(reader, path_name).await?) {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    async fn read_block_with_mode<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<Option<(VecDataBlock<T>, BlockMode)>, Error>
+        where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+            T: ReflectedType,
+    {
+        self.read_block_with_mode_and_provenance(path_name, data_attrs, grid_position).await
+            .map(|(block, _provenance)| block)
+    }
+
+    /// Union of the children listed by each layer. Layers that cannot list
+    /// are skipped, unless no layer can.
+    async fn list(&self, path_name: &str) -> Result<Vec<String>, Error> {
+        let mut children = BTreeSet::new();
+        let mut first_error = None;
+        let mut any_listed = false;
+        for layer in self.layers() {
+            match layer_dispatch!(&layer.reader, reader => N5AsyncReader::list(reader, path_name).await) {
+                Ok(layer_children) => {
+                    any_listed = true;
+                    children.extend(layer_children);
+                },
+                Err(error) => {
+                    first_error.get_or_insert(error);
+                },
+            }
+        }
+
+        match first_error {
+            Some(error) if !any_listed => Err(error),
+            _ => Ok(children.into_iter().collect()),
+        }
+    }
+
+    async fn list_attributes(&self, path_name: &str) -> Result<serde_json::Value, Error> {
+        self.merged_attributes(path_name).await.map(serde_json::Value::Object)
+    }
+}
+
+#[async_trait(?Send)]
+impl N5AsyncObjectReader for N5Overlay {
+    /// Blocks are read from the uppermost layer that has them and whose
+    /// tombstones, or those of the layers above, do not mask them.
+    async fn read_block_bytes(
+        &self,
+        path_name: &str,
+        grid_position: &[u64],
+    ) -> Result<Option<Vec<u8>>, Error> {
+        for layer in self.layers().iter().rev() {
+            if layer.dataset_tombstones(path_name).await?.contains(grid_position) {
+                return Ok(None);
+            }
+
+            let bytes = layer_dispatch!(&layer.reader, reader =>
+                N5AsyncObjectReader::read_block_bytes(reader, path_name, grid_position).await?);
+            if bytes.is_some() {
+                return Ok(bytes);
+            }
+        }
+
+        Ok(None)
+    }
+}
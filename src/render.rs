@@ -0,0 +1,516 @@
+//! Conversion of data to RGBA pixels for canvases, either intensities with
+//! a window, gamma and an optional lookup table of colors, or segmentation
+//! labels with hashed colors.
+
+use std::cell::RefCell;
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+use wasm_bindgen::Clamped;
+use web_sys::ImageData;
+
+use super::*;
+
+
+const LUT_SIZE: usize = 256;
+
+
+#[wasm_bindgen(typescript_custom_section)]
+const RENDER_TYPES: &str = r#"
+/** Data to render: a typed array, or a `DataBlock`, `Slice` or `Reslice`. */
+export type RenderSource = TypedArrayFor<DataType> | { readonly data: TypedArrayFor<DataType> };
+
+export interface RenderOptions {
+  /** Values at or below `min` have the darkest color. */
+  min: number;
+  /** Values at or above `max` have the brightest color. */
+  max: number;
+  /** Exponent applied to windowed intensities. Defaults to 1. */
+  gamma?: number;
+  /** 256 RGBA colors indexed by windowed intensity, such as from `colormap`. */
+  lut?: Uint8Array | Uint8ClampedArray;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "RenderSource")]
+    pub type RenderSource;
+
+    #[wasm_bindgen(typescript_type = "RenderOptions")]
+    pub type RenderOptionsJson;
+}
+
+/// Intensity window and colors for rendering.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderOptions {
+    pub min: f64,
+    pub max: f64,
+    pub gamma: f64,
+    /// 256 RGBA colors, or grayscale if none.
+    pub lut: Option<Vec<u8>>,
+}
+
+impl RenderOptions {
+    pub fn new(min: f64, max: f64) -> RenderOptions {
+        RenderOptions {
+            min,
+            max,
+            gamma: 1.,
+            lut: None,
+        }
+    }
+
+    /// Check that the window is finite and ordered and that gamma is finite
+    /// and positive, without which intensities are not in the unit interval.
+    pub fn validate(&self) -> Result<(), Error> {
+        if !self.min.is_finite() || !self.max.is_finite() {
+            return Err(Error::new(ErrorKind::InvalidInput, "Render window bounds must be finite"));
+        }
+        if self.min > self.max {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Render window minimum {} is above its maximum {}", self.min, self.max)));
+        }
+        if !self.gamma.is_finite() || self.gamma <= 0. {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Render gamma must be finite and positive, not {}", self.gamma)));
+        }
+
+        Ok(())
+    }
+
+    /// Index into the lookup table of a value.
+    fn lut_index(&self, value: f64) -> usize {
+        let intensity = if self.max > self.min {
+            ((value - self.min) / (self.max - self.min)).max(0.).min(1.)
+        } else if value >= self.max {
+            1.
+        } else {
+            0.
+        };
+        let intensity = if self.gamma == 1. { intensity } else { intensity.powf(self.gamma) };
+
+        (intensity * (LUT_SIZE - 1) as f64).round() as usize
+    }
+}
+
+impl RenderOptionsJson {
+    fn to_options(&self) -> Result<RenderOptions, Error> {
+        let get = |key: &str| js_sys::Reflect::get(self, &key.into()).map_err(convert_jsvalue_error);
+        let number = |key: &str| get(key)?.as_f64().ok_or_else(|| Error::new(
+            ErrorKind::InvalidInput,
+            format!("Render option `{}` must be a number", key)));
+
+        let gamma = get("gamma")?;
+        let lut = get("lut")?;
+
+        let options = RenderOptions {
+            min: number("min")?,
+            max: number("max")?,
+            gamma: if gamma.is_undefined() { 1. } else { number("gamma")? },
+            lut: if lut.is_undefined() {
+                None
+            } else {
+                let lut = js_sys::Uint8Array::new(&lut).to_vec();
+                if lut.len() != LUT_SIZE * 4 {
+                    return Err(Error::new(ErrorKind::InvalidInput, "Lookup tables must have 256 RGBA colors"));
+                }
+                Some(lut)
+            },
+        };
+        options.validate()?;
+
+        Ok(options)
+    }
+}
+
+/// Render data to RGBA pixels, four bytes per element.
+pub fn render_rgba<T: Numeric>(
+    data: &[T],
+    options: &RenderOptions,
+    rgba: &mut [u8],
+) -> Result<(), Error> {
+    if rgba.len() != data.len() * 4 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Output has {} bytes, but {} elements need {}", rgba.len(), data.len(), data.len() * 4)));
+    }
+
+    for (&value, pixel) in data.iter().zip(rgba.chunks_exact_mut(4)) {
+        let index = options.lut_index(value.to_f64());
+        match &options.lut {
+            Some(lut) => pixel.copy_from_slice(&lut[index * 4..index * 4 + 4]),
+            None => pixel.copy_from_slice(&[index as u8, index as u8, index as u8, 255]),
+        }
+    }
+
+    Ok(())
+}
+
+/// A lookup table of 256 RGBA colors for a named colormap: `gray`, `hot`,
+/// `cool`, `red`, `green` or `blue`.
+pub fn colormap_lut(name: &str) -> Option<Vec<u8>> {
+    let color: fn(f64) -> [f64; 3] = match name {
+        "gray" => |t| [t, t, t],
+        "hot" => |t| [(3. * t).min(1.), (3. * t - 1.).max(0.).min(1.), (3. * t - 2.).max(0.)],
+        "cool" => |t| [t, 1. - t, 1.],
+        "red" => |t| [t, 0., 0.],
+        "green" => |t| [0., t, 0.],
+        "blue" => |t| [0., 0., t],
+        _ => return None,
+    };
+
+    Some((0..LUT_SIZE)
+        .flat_map(|i| {
+            let [r, g, b] = color(i as f64 / (LUT_SIZE - 1) as f64);
+            vec![
+                (r * 255.).round() as u8,
+                (g * 255.).round() as u8,
+                (b * 255.).round() as u8,
+                255,
+            ]
+        })
+        .collect())
+}
+
+/// Evaluate an expression with `$data` bound to a copy of the contents of
+/// any typed array of an N5 data type.
+macro_rules! typed_array_match {
+    ($array:expr, $data:ident => $body:expr, $otherwise:expr) => {{
+        let array: &JsValue = $array;
+        if let Some(typed) = array.dyn_ref::<js_sys::Uint8Array>() {
+            let $data = typed.to_vec();
+            $body
+        } else if let Some(typed) = array.dyn_ref::<js_sys::Uint16Array>() {
+            let $data = typed.to_vec();
+            $body
+        } else if let Some(typed) = array.dyn_ref::<js_sys::Uint32Array>() {
+            let $data = typed.to_vec();
+            $body
+        } else if let Some(typed) = array.dyn_ref::<js_sys::BigUint64Array>() {
+            let $data = typed.to_vec();
+            $body
+        } else if let Some(typed) = array.dyn_ref::<js_sys::Int8Array>() {
+            let $data = typed.to_vec();
+            $body
+        } else if let Some(typed) = array.dyn_ref::<js_sys::Int16Array>() {
+            let $data = typed.to_vec();
+            $body
+        } else if let Some(typed) = array.dyn_ref::<js_sys::Int32Array>() {
+            let $data = typed.to_vec();
+            $body
+        } else if let Some(typed) = array.dyn_ref::<js_sys::BigInt64Array>() {
+            let $data = typed.to_vec();
+            $body
+        } else if let Some(typed) = array.dyn_ref::<js_sys::Float32Array>() {
+            let $data = typed.to_vec();
+            $body
+        } else if let Some(typed) = array.dyn_ref::<js_sys::Float64Array>() {
+            let $data = typed.to_vec();
+            $body
+        } else {
+            $otherwise
+        }
+    }}
+}
+
+/// The typed array of a render source, which is either a typed array or an
+/// object with a `data` typed array.
+fn source_data(source: &RenderSource) -> Result<JsValue, Error> {
+    if js_sys::ArrayBuffer::is_view(source) {
+        Ok(JsValue::clone(source))
+    } else {
+        js_sys::Reflect::get(source, &"data".into()).map_err(convert_jsvalue_error)
+    }
+}
+
+fn render_source(source: &RenderSource, options: &RenderOptionsJson, rgba: &mut [u8]) -> Result<(), Error> {
+    let options = options.to_options()?;
+
+    typed_array_match!(
+        &source_data(source)?,
+        data => render_rgba(&data, &options, rgba),
+        Err(Error::new(ErrorKind::InvalidInput, "Render source has no typed array data")))
+}
+
+fn to_js_error(error: Error) -> JsValue {
+    js_sys::Error::new(&error.to_string()).into()
+}
+
+/// Render data into RGBA pixels, such as the `data` of an `ImageData` of
+/// the same width and height.
+#[wasm_bindgen]
+pub fn render(
+    source: &RenderSource,
+    options: &RenderOptionsJson,
+    output: Clamped<&mut [u8]>,
+) -> Result<(), JsValue> {
+    let Clamped(rgba) = output;
+    render_source(source, options, rgba).map_err(to_js_error)
+}
+
+/// Render data into a new `ImageData`.
+#[wasm_bindgen]
+pub fn render_image_data(
+    source: &RenderSource,
+    width: u32,
+    height: u32,
+    options: &RenderOptionsJson,
+) -> Result<ImageData, JsValue> {
+    let mut rgba = vec![0; width as usize * height as usize * 4];
+    render_source(source, options, &mut rgba).map_err(to_js_error)?;
+
+    ImageData::new_with_u8_clamped_array_and_sh(Clamped(&mut rgba), width, height)
+}
+
+/// A lookup table of 256 RGBA colors for the `lut` render option, for a
+/// colormap named `gray`, `hot`, `cool`, `red`, `green` or `blue`.
+#[wasm_bindgen]
+pub fn colormap(name: &str) -> Result<js_sys::Uint8Array, JsValue> {
+    colormap_lut(name)
+        .map(|lut| js_sys::Uint8Array::from(&lut[..]))
+        .ok_or_else(|| js_sys::Error::new(&format!("Unknown colormap: {}", name)).into())
+}
+
+
+/// Stable pseudo-random colors for segmentation labels, with highlighting of
+/// selected segments. Label 0 is background and transparent.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SegmentColors {
+    /// Seed varying the color of every label.
+    pub seed: u64,
+    /// Selected labels, each of which selects its whole merged segment.
+    pub selected: HashSet<u64>,
+    /// Merged segments, as a union-find forest of the parent of each merged
+    /// label. Paths are compressed as representatives are found.
+    equivalences: RefCell<HashMap<u64, u64>>,
+    /// Alpha of segments outside a non-empty selection, by default 64.
+    pub unselected_alpha: u8,
+}
+
+/// Mix the bits of a label, as in the finalizer of MurmurHash3.
+fn mix_label(mut hash: u64) -> u64 {
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    hash ^ (hash >> 33)
+}
+
+impl Default for SegmentColors {
+    fn default() -> Self {
+        SegmentColors {
+            seed: 0,
+            selected: HashSet::new(),
+            equivalences: RefCell::new(HashMap::new()),
+            unselected_alpha: 64,
+        }
+    }
+}
+
+impl SegmentColors {
+    pub fn new(seed: u64) -> SegmentColors {
+        SegmentColors {
+            seed,
+            ..Default::default()
+        }
+    }
+
+    /// The label a label's merged segment is colored as.
+    pub fn representative(&self, label: u64) -> u64 {
+        let mut parents = self.equivalences.borrow_mut();
+        let mut root = label;
+        while let Some(&parent) = parents.get(&root) {
+            root = parent;
+        }
+
+        let mut current = label;
+        while current != root {
+            let parent = parents[&current];
+            parents.insert(current, root);
+            current = parent;
+        }
+
+        root
+    }
+
+    /// Merge the segment of `label` into that of `representative`, so that
+    /// both are colored and selected as its representative.
+    pub fn set_equivalent(&mut self, label: u64, representative: u64) {
+        let label = self.representative(label);
+        let representative = self.representative(representative);
+        if label != representative {
+            self.equivalences.get_mut().insert(label, representative);
+        }
+    }
+
+    pub fn clear_equivalences(&mut self) {
+        self.equivalences.get_mut().clear();
+    }
+
+    /// Deselect the merged segment of a label, whichever of its labels were
+    /// selected.
+    pub fn deselect(&mut self, label: u64) {
+        let representative = self.representative(label);
+        let selected: Vec<u64> = self.selected.iter().copied().collect();
+        for label in selected {
+            if self.representative(label) == representative {
+                self.selected.remove(&label);
+            }
+        }
+    }
+
+    fn selected_representatives(&self) -> HashSet<u64> {
+        self.selected.iter().map(|&label| self.representative(label)).collect()
+    }
+
+    /// RGBA color of a label.
+    pub fn color(&self, label: u64) -> [u8; 4] {
+        self.segment_color(label, &self.selected_representatives())
+    }
+
+    /// RGBA color of a label, given the representatives of the selection.
+    fn segment_color(&self, label: u64, selected: &HashSet<u64>) -> [u8; 4] {
+        let label = self.representative(label);
+        if label == 0 {
+            return [0; 4];
+        }
+
+        // Fully saturated hues at varied brightness stay distinct from
+        // each other and from grayscale backgrounds.
+        let hash = mix_label(label ^ mix_label(self.seed));
+        let hue = (hash & 0xffff) as f64 / 65536. * 6.;
+        let saturation = 0.5 + ((hash >> 16) & 0xff) as f64 / 255. * 0.5;
+        let value = 0.7 + ((hash >> 24) & 0xff) as f64 / 255. * 0.3;
+
+        let chroma = value * saturation;
+        let x = chroma * (1. - (hue % 2. - 1.).abs());
+        let [r, g, b] = match hue as u32 {
+            0 => [chroma, x, 0.],
+            1 => [x, chroma, 0.],
+            2 => [0., chroma, x],
+            3 => [0., x, chroma],
+            4 => [x, 0., chroma],
+            _ => [chroma, 0., x],
+        };
+        let m = value - chroma;
+        let alpha = if selected.is_empty() || selected.contains(&label) {
+            255
+        } else {
+            self.unselected_alpha
+        };
+
+        [
+            ((r + m) * 255.).round() as u8,
+            ((g + m) * 255.).round() as u8,
+            ((b + m) * 255.).round() as u8,
+            alpha,
+        ]
+    }
+
+    /// Render labels to RGBA pixels, four bytes per element.
+    pub fn render_rgba<T: Copy + Into<u64>>(&self, labels: &[T], rgba: &mut [u8]) -> Result<(), Error> {
+        if rgba.len() != labels.len() * 4 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Output has {} bytes, but {} elements need {}", rgba.len(), labels.len(), labels.len() * 4)));
+        }
+
+        // Segmentations are mostly runs of the same label.
+        let selected = self.selected_representatives();
+        let mut last: Option<(u64, [u8; 4])> = None;
+        for (&label, pixel) in labels.iter().zip(rgba.chunks_exact_mut(4)) {
+            let label = label.into();
+            let color = match last {
+                Some((last_label, color)) if last_label == label => color,
+                _ => self.segment_color(label, &selected),
+            };
+            last = Some((label, color));
+            pixel.copy_from_slice(&color);
+        }
+
+        Ok(())
+    }
+
+    fn render_source(&self, source: &RenderSource, rgba: &mut [u8]) -> Result<(), Error> {
+        let data = source_data(source)?;
+        if let Some(labels) = data.dyn_ref::<js_sys::BigUint64Array>() {
+            self.render_rgba(&labels.to_vec(), rgba)
+        } else if let Some(labels) = data.dyn_ref::<js_sys::Uint32Array>() {
+            self.render_rgba(&labels.to_vec(), rgba)
+        } else {
+            Err(Error::new(ErrorKind::InvalidInput, "Segmentations must be uint32 or uint64 data"))
+        }
+    }
+}
+
+/// Colors for rendering uint32 or uint64 segmentations, as exposed to JS.
+#[wasm_bindgen]
+pub struct SegmentColorizer(SegmentColors);
+
+#[wasm_bindgen]
+impl SegmentColorizer {
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: Option<Coordinate>) -> Result<SegmentColorizer, JsValue> {
+        let seed = seed.map(|seed| seed.to_u64()).transpose().map_err(to_js_error)?;
+        Ok(SegmentColorizer(SegmentColors::new(seed.unwrap_or(0))))
+    }
+
+    /// Alpha, from 0 to 255, of segments outside a non-empty selection.
+    #[wasm_bindgen(getter, js_name = unselectedAlpha)]
+    pub fn unselected_alpha(&self) -> u8 {
+        self.0.unselected_alpha
+    }
+
+    #[wasm_bindgen(setter, js_name = unselectedAlpha)]
+    pub fn set_unselected_alpha(&mut self, alpha: u8) {
+        self.0.unselected_alpha = alpha;
+    }
+
+    pub fn select(&mut self, label: Coordinate) -> Result<(), JsValue> {
+        let label = label.to_u64().map_err(to_js_error)?;
+        self.0.selected.insert(label);
+        Ok(())
+    }
+
+    /// Deselect the merged segment of a label.
+    pub fn deselect(&mut self, label: Coordinate) -> Result<(), JsValue> {
+        let label = label.to_u64().map_err(to_js_error)?;
+        self.0.deselect(label);
+        Ok(())
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.0.selected.clear();
+    }
+
+    /// Merge the segment of `label` into that of `representative`, so that
+    /// both are colored and selected as one.
+    pub fn set_equivalent(&mut self, label: Coordinate, representative: Coordinate) -> Result<(), JsValue> {
+        let label = label.to_u64().map_err(to_js_error)?;
+        let representative = representative.to_u64().map_err(to_js_error)?;
+        self.0.set_equivalent(label, representative);
+        Ok(())
+    }
+
+    pub fn clear_equivalences(&mut self) {
+        self.0.clear_equivalences();
+    }
+
+    /// RGBA color of a label.
+    pub fn color(&self, label: Coordinate) -> Result<Vec<u8>, JsValue> {
+        let label = label.to_u64().map_err(to_js_error)?;
+        Ok(self.0.color(label).to_vec())
+    }
+
+    /// Render uint32 or uint64 labels into RGBA pixels, such as the `data`
+    /// of an `ImageData` of the same width and height.
+    pub fn render(&self, source: &RenderSource, output: Clamped<&mut [u8]>) -> Result<(), JsValue> {
+        let Clamped(rgba) = output;
+        self.0.render_source(source, rgba).map_err(to_js_error)
+    }
+}
@@ -0,0 +1,305 @@
+//! Resampling of arbitrarily oriented planes through 3D datasets.
+//!
+//! Voxel centers are at integer coordinates, so a plane through
+//! `[2, 3, 4]` samples that voxel exactly. Samples farther than half a voxel
+//! outside the dataset are zero.
+
+use std::collections::BTreeSet;
+
+use super::*;
+
+
+/// Most samples in a reslice, such as 4096 by 4096, to bound the memory a
+/// caller can request.
+pub const MAX_RESLICE_SAMPLES: usize = 1 << 24;
+
+#[wasm_bindgen(typescript_custom_section)]
+const RESLICE_TYPES: &str = r#"
+/** An oriented plane of samples through a 3D dataset, in voxel coordinates. */
+export interface Plane {
+  /** Coordinates of the first sample. */
+  origin: [number, number, number];
+  /** Direction of the rows of samples. */
+  u: [number, number, number];
+  /** Direction of the columns of samples. */
+  v: [number, number, number];
+  /** Number of samples along `u` and `v`, at most 2^24 in total. */
+  size: [number, number];
+  /** Distance between samples along `u` and `v`, in voxels. Defaults to 1. */
+  spacing?: [number, number];
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "Plane")]
+    pub type PlaneJson;
+
+    #[wasm_bindgen(typescript_type = "Promise<Reslice>")]
+    pub type PromiseReslice;
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Interpolation {
+    /// The value of the closest voxel, which preserves labels exactly.
+    Nearest = 0,
+    /// Trilinear interpolation of the eight surrounding voxels, rounded for
+    /// integer data types.
+    Trilinear = 1,
+}
+
+fn default_spacing() -> [f64; 2] {
+    [1., 1.]
+}
+
+/// An oriented plane of samples through a 3D dataset, in voxel coordinates.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize)]
+pub struct Plane {
+    /// Coordinates of the first sample.
+    pub origin: [f64; 3],
+    /// Direction of the rows of samples, which need not be normalized.
+    pub u: [f64; 3],
+    /// Direction of the columns of samples, which need not be normalized.
+    pub v: [f64; 3],
+    /// Number of samples along `u` and `v`.
+    pub size: [u32; 2],
+    /// Distance between samples along `u` and `v`, in voxels.
+    #[serde(default = "default_spacing")]
+    pub spacing: [f64; 2],
+}
+
+impl Plane {
+    /// Coordinates of every sample, in rows along `u`.
+    fn sample_points(&self) -> Result<Vec<[f64; 3]>, Error> {
+        let step = |direction: &[f64; 3], spacing: f64| -> Result<[f64; 3], Error> {
+            let norm = direction.iter().map(|d| d * d).sum::<f64>().sqrt();
+            if norm <= 0. || !norm.is_finite() {
+                return Err(Error::new(ErrorKind::InvalidInput, "Plane directions must be non-zero"));
+            }
+            Ok([
+                direction[0] / norm * spacing,
+                direction[1] / norm * spacing,
+                direction[2] / norm * spacing,
+            ])
+        };
+        let step_u = step(&self.u, self.spacing[0])?;
+        let step_v = step(&self.v, self.spacing[1])?;
+
+        let num_samples = (self.size[0] as usize).checked_mul(self.size[1] as usize)
+            .filter(|&num_samples| num_samples <= MAX_RESLICE_SAMPLES)
+            .ok_or_else(|| Error::new(
+                ErrorKind::InvalidInput,
+                format!("Reslices are limited to {} samples", MAX_RESLICE_SAMPLES)))?;
+
+        let mut points = Vec::with_capacity(num_samples);
+        for j in 0..self.size[1] {
+            for i in 0..self.size[0] {
+                let (i, j) = (f64::from(i), f64::from(j));
+                points.push([
+                    self.origin[0] + i * step_u[0] + j * step_v[0],
+                    self.origin[1] + i * step_u[1] + j * step_v[1],
+                    self.origin[2] + i * step_u[2] + j * step_v[2],
+                ]);
+            }
+        }
+
+        Ok(points)
+    }
+}
+
+/// Voxels contributing to a sample and their weights, or none if the sample
+/// is outside the dataset.
+fn sample_voxels(
+    point: &[f64; 3],
+    dimensions: &[u64],
+    interpolation: Interpolation,
+) -> Vec<([u64; 3], f64)> {
+    let outside = point.iter().zip(dimensions)
+        .any(|(&p, &dim)| p.is_nan() || p < -0.5 || p >= dim as f64 - 0.5);
+    if outside {
+        return Vec::new();
+    }
+
+    match interpolation {
+        Interpolation::Nearest => {
+            let mut voxel = [0; 3];
+            for ((v, &p), &dim) in voxel.iter_mut().zip(point).zip(dimensions) {
+                *v = ((p + 0.5).floor() as u64).min(dim - 1);
+            }
+            vec![(voxel, 1.)]
+        },
+        Interpolation::Trilinear => {
+            // Neighbors beyond the dataset's edge are clamped to it.
+            let mut lower = [0; 3];
+            let mut upper = [0; 3];
+            let mut fraction = [0.; 3];
+            for ((((&p, &dim), l), u), f) in point.iter()
+                    .zip(dimensions)
+                    .zip(&mut lower)
+                    .zip(&mut upper)
+                    .zip(&mut fraction) {
+                let floor = p.floor();
+                *f = p - floor;
+                *l = floor.max(0.) as u64;
+                *u = ((floor + 1.).max(0.) as u64).min(dim - 1);
+            }
+
+            let mut voxels = Vec::with_capacity(8);
+            for corner in 0..8 {
+                let mut voxel = [0; 3];
+                let mut weight = 1.;
+                for (d, v) in voxel.iter_mut().enumerate() {
+                    if corner & (1 << d) == 0 {
+                        *v = lower[d];
+                        weight *= 1. - fraction[d];
+                    } else {
+                        *v = upper[d];
+                        weight *= fraction[d];
+                    }
+                }
+                if weight > 0. {
+                    voxels.push((voxel, weight));
+                }
+            }
+            voxels
+        },
+    }
+}
+
+/// Samples of a plane through a dataset.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reslice<T> {
+    /// Number of samples along the plane's `u` and `v` directions.
+    pub size: [u32; 2],
+    /// Samples in rows along `u`.
+    pub data: Vec<T>,
+}
+
+/// Resampling oriented planes of 3D datasets, for any reader.
+#[async_trait(?Send)]
+pub trait N5AsyncResliceReader: N5AsyncReader {
+    /// Sample a plane through a 3D dataset, reading only the blocks the
+    /// samples need. Voxels of missing blocks are zero.
+    async fn reslice<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        plane: &Plane,
+        interpolation: Interpolation,
+    ) -> Result<Reslice<T>, Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: Numeric,
+    {
+        let dimensions = data_attrs.get_dimensions();
+        if dimensions.len() != 3 {
+            return Err(Error::new(ErrorKind::InvalidInput, "Reslicing requires a 3D dataset"));
+        }
+
+        let samples: Vec<_> = plane.sample_points()?.iter()
+            .map(|point| sample_voxels(point, dimensions, interpolation))
+            .collect();
+
+        let block_size: Vec<u64> = data_attrs.get_block_size().iter().map(|&b| u64::from(b)).collect();
+        let grid_positions: BTreeSet<Vec<u64>> = samples.iter()
+            .flatten()
+            .map(|(voxel, _)| VoxelLookup::<T>::grid_position(&block_size, voxel))
+            .collect();
+        let lookup = VoxelLookup::<T>::read(self, path_name, data_attrs, grid_positions).await?;
+
+        let data = samples.iter()
+            .map(|voxels| match interpolation {
+                Interpolation::Nearest => voxels.first()
+                    .and_then(|(voxel, _)| lookup.get(voxel))
+                    .unwrap_or_default(),
+                Interpolation::Trilinear if voxels.is_empty() => T::default(),
+                Interpolation::Trilinear => T::from_f64(voxels.iter()
+                    .map(|(voxel, weight)| weight * lookup.get(voxel).unwrap_or_default().to_f64())
+                    .sum()),
+            })
+            .collect();
+
+        Ok(Reslice {
+            size: plane.size,
+            data,
+        })
+    }
+}
+
+impl<R: N5AsyncReader> N5AsyncResliceReader for R {}
+
+
+pub trait N5PromiseResliceReader {
+    /// Resolve to the `Reslice` of samples of a plane through a 3D dataset.
+    fn reslice(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        plane: PlaneJson,
+        interpolation: Interpolation,
+    ) -> PromiseReslice;
+}
+
+impl<T> N5PromiseResliceReader for T where T: N5AsyncReader + Clone + 'static {
+    fn reslice(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        plane: PlaneJson,
+        interpolation: Interpolation,
+    ) -> PromiseReslice {
+
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let data_attrs = data_attrs.0.clone();
+        let data_type = data_attrs.get_data_type().clone();
+
+        data_type_match! {
+            data_type,
+            future_to_promise(map_future_error_wasm(async move {
+                let plane: Plane = plane.into_serde()?;
+                let reslice = N5AsyncResliceReader::reslice::<RsType>(
+                    &reader, &path_name, &data_attrs, &plane, interpolation).await?;
+                Ok(JsValue::from(JsReslice {
+                    data_type: data_attrs.get_data_type().clone(),
+                    size: reslice.size,
+                    data: <RsType as VecBlockMonomorphizerReflection>::data_to_js(&reslice.data),
+                }))
+            })).unchecked_into()
+        }
+    }
+}
+
+
+/// Samples of a plane through a dataset, as exposed to JS.
+#[wasm_bindgen(js_name = Reslice)]
+pub struct JsReslice {
+    data_type: DataType,
+    size: [u32; 2],
+    data: JsValue,
+}
+
+#[wasm_bindgen(js_class = Reslice)]
+impl JsReslice {
+    #[wasm_bindgen(getter)]
+    pub fn dtype(&self) -> DataTypeName {
+        JsValue::from(self.data_type.to_string()).unchecked_into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> u32 {
+        self.size[0]
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> u32 {
+        self.size[1]
+    }
+
+    /// The samples in rows of `width` along `u`, in the typed array for
+    /// `dtype`.
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> BlockData {
+        self.data.clone().unchecked_into()
+    }
+}
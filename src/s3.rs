@@ -0,0 +1,829 @@
+//! N5 containers in S3-compatible object stores, such as AWS S3 or MinIO,
+//! with requests signed by AWS Signature Version 4.
+//!
+//! S3 has no directories, so groups are represented by empty marker objects
+//! whose keys end in `/`, as written by the Java N5 S3 backend.
+
+use std::fmt::Write as _;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use hmac::{
+    Hmac,
+    Mac,
+    NewMac,
+};
+use sha2::{
+    Digest,
+    Sha256,
+};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::Response;
+
+use super::*;
+use crate::object::{
+    N5AsyncObjectReader,
+    N5AsyncObjectWriter,
+    N5PromiseObjectReader,
+    N5PromiseObjectWriter,
+};
+use crate::transport::{
+    GlobalFetch,
+    JsCallbackTransport,
+    Transport,
+};
+
+
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Token of temporary credentials, such as those from STS.
+    #[serde(default)]
+    pub session_token: Option<String>,
+}
+
+/// Location of a container within a bucket, deserialized from a JS object
+/// with camel case keys.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3Config {
+    /// Service endpoint without a path, such as
+    /// `https://s3.us-east-1.amazonaws.com` or `http://localhost:9000`.
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    /// Key prefix of the container root within the bucket.
+    #[serde(default)]
+    pub prefix: String,
+    /// Address the bucket as the first path component rather than as a
+    /// subdomain of the endpoint. MinIO requires this by default.
+    #[serde(default)]
+    pub path_style: bool,
+    /// Requests are unsigned if no credentials are given, for public buckets.
+    #[serde(default)]
+    pub credentials: Option<S3Credentials>,
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const S3_CONFIG_TYPE: &str = r#"
+/** Configuration of an `N5S3` container. */
+export interface S3Config {
+  endpoint: string;
+  region: string;
+  bucket: string;
+  prefix?: string;
+  pathStyle?: boolean;
+  credentials?: {
+    accessKeyId: string;
+    secretAccessKey: string;
+    sessionToken?: string;
+  };
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "S3Config")]
+    pub type S3ConfigJson;
+}
+
+/// The parts of a request covered by its signature.
+pub struct SignableRequest<'a> {
+    pub method: &'a str,
+    pub host: &'a str,
+    /// URI-encoded absolute path.
+    pub path: &'a str,
+    /// Query parameters, not yet encoded.
+    pub query: &'a [(&'a str, &'a str)],
+    /// Headers to sign besides `host` and the `x-amz-*` headers added by
+    /// signing.
+    pub headers: &'a [(&'a str, &'a str)],
+    /// Hex SHA-256 of the request body.
+    pub payload_hash: &'a str,
+}
+
+impl S3Credentials {
+    /// Sign a request at a time formatted as `YYYYMMDDTHHMMSSZ`, returning
+    /// the headers to add to it.
+    pub fn sign(
+        &self,
+        region: &str,
+        amz_date: &str,
+        request: &SignableRequest,
+    ) -> Vec<(String, String)> {
+        let date = &amz_date[..8];
+        let scope = format!("{}/{}/s3/aws4_request", date, region);
+
+        let mut added = vec![
+            ("x-amz-content-sha256".to_owned(), request.payload_hash.to_owned()),
+            ("x-amz-date".to_owned(), amz_date.to_owned()),
+        ];
+        if let Some(token) = &self.session_token {
+            added.push(("x-amz-security-token".to_owned(), token.clone()));
+        }
+
+        let mut canonical_headers: Vec<(String, String)> = request.headers.iter()
+            .map(|(name, value)| (name.to_lowercase(), value.trim().to_owned()))
+            .chain(std::iter::once(("host".to_owned(), request.host.to_owned())))
+            .chain(added.iter().cloned())
+            .collect();
+        canonical_headers.sort();
+        let signed_headers = canonical_headers.iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(";");
+
+        let mut canonical_request = format!(
+            "{}\n{}\n{}\n",
+            request.method,
+            request.path,
+            canonical_query(request.query));
+        for (name, value) in &canonical_headers {
+            writeln!(canonical_request, "{}:{}", name, value).unwrap();
+        }
+        write!(canonical_request, "\n{}\n{}", signed_headers, request.payload_hash).unwrap();
+
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex(&Sha256::digest(canonical_request.as_bytes())));
+
+        let signing_key = [date, region, "s3", "aws4_request"].iter()
+            .fold(
+                format!("AWS4{}", self.secret_access_key).into_bytes(),
+                |key, part| hmac_sha256(&key, part.as_bytes()));
+        let signature = hex(&hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+        added.push((
+            "authorization".to_owned(),
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                self.access_key_id,
+                scope,
+                signed_headers,
+                signature)));
+
+        added
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_varkey(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn canonical_query(query: &[(&str, &str)]) -> String {
+    let mut params: Vec<(String, String)> = query.iter()
+        .map(|(name, value)| (uri_encode(name, true), uri_encode(value, true)))
+        .collect();
+    params.sort();
+
+    params.iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Current time in the `x-amz-date` format, from an ISO string such as
+/// `2013-05-24T00:00:00.000Z`.
+fn amz_date() -> String {
+    let iso = String::from(js_sys::Date::new_0().to_iso_string());
+    let mut date: String = iso[..19].chars().filter(|c| *c != '-' && *c != ':').collect();
+    date.push('Z');
+
+    date
+}
+
+fn check_status(resp: &Response, action: &str) -> Result<(), Error> {
+    let kind = match resp.status() {
+        _ if resp.ok() => return Ok(()),
+        401 | 403 => ErrorKind::PermissionDenied,
+        404 => ErrorKind::NotFound,
+        _ => ErrorKind::Other,
+    };
+
+    Err(Error::new(kind, format!("S3 {} failed with status {}", action, resp.status())))
+}
+
+async fn response_bytes(resp: &Response) -> Result<Vec<u8>, JsValue> {
+    let arrbuff_value = JsFuture::from(resp.array_buffer()?).await?;
+
+    Ok(js_sys::Uint8Array::new(&arrbuff_value).to_vec())
+}
+
+async fn response_text(resp: &Response) -> Result<String, JsValue> {
+    let text = JsFuture::from(resp.text()?).await?;
+
+    Ok(text.as_string().unwrap_or_default())
+}
+
+
+#[derive(Default)]
+struct ObjectListing {
+    keys: Vec<String>,
+    common_prefixes: Vec<String>,
+}
+
+
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct N5S3 {
+    config: Rc<S3Config>,
+    scheme: String,
+    host: String,
+    transport: Rc<dyn Transport>,
+}
+
+impl N5S3 {
+    fn new(config: S3Config, transport: Rc<dyn Transport>) -> Result<N5S3, Error> {
+        let mut parts = config.endpoint.splitn(2, "://");
+        let (scheme, authority) = match (parts.next(), parts.next()) {
+            (Some(scheme), Some(authority)) => (scheme.to_owned(), authority.trim_end_matches('/')),
+            _ => return Err(Error::new(ErrorKind::InvalidInput, "S3 endpoint must be a URL")),
+        };
+        if authority.contains('/') {
+            return Err(Error::new(ErrorKind::InvalidInput, "S3 endpoint must not have a path"));
+        }
+        let host = if config.path_style {
+            authority.to_owned()
+        } else {
+            format!("{}.{}", config.bucket, authority)
+        };
+
+        Ok(N5S3 {
+            config: Rc::new(config),
+            scheme,
+            host,
+            transport,
+        })
+    }
+
+    /// Open a container from Rust, checking that its N5 version is compatible.
+    pub async fn open_reader(config: S3Config) -> Result<N5S3, Error> {
+        Self::open_reader_with_transport(config, Rc::new(GlobalFetch)).await
+    }
+
+    /// Open a container from Rust, making all requests through `transport`.
+    pub async fn open_reader_with_transport(
+        config: S3Config,
+        transport: Rc<dyn Transport>,
+    ) -> Result<N5S3, Error> {
+        let reader = Self::new(config, transport)?;
+
+        let version = N5AsyncReader::get_version(&reader).await?;
+
+        if !n5::is_version_compatible(&n5::VERSION, &version) {
+            return Err(Error::new(ErrorKind::Other, "TODO: Incompatible version"))
+        }
+
+        Ok(reader)
+    }
+
+    /// Create a new container from Rust, or open an existing one.
+    pub async fn create_writer_with_transport(
+        config: S3Config,
+        transport: Rc<dyn Transport>,
+    ) -> Result<N5S3, Error> {
+        let writer = Self::new(config, transport)?;
+
+        let mut version = serde_json::Map::new();
+        version.insert(
+            n5::VERSION_ATTRIBUTE_KEY.to_owned(),
+            serde_json::Value::String(n5::VERSION.to_string()));
+        N5AsyncWriter::set_attributes(&writer, "", version).await?;
+
+        Ok(writer)
+    }
+
+    fn object_key(&self, path_name: &str) -> String {
+        [self.config.prefix.as_str(), path_name].iter()
+            .map(|part| part.trim_matches('/'))
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Key prefix of all objects within a group.
+    fn directory_prefix(&self, path_name: &str) -> String {
+        let key = self.object_key(path_name);
+        if key.is_empty() {
+            key
+        } else {
+            key + "/"
+        }
+    }
+
+    async fn request(
+        &self,
+        method: &str,
+        key: &str,
+        query: &[(&str, &str)],
+        body: Option<&[u8]>,
+    ) -> Result<Response, JsValue> {
+        let path = if self.config.path_style {
+            format!("/{}/{}", self.config.bucket, uri_encode(key, false))
+        } else {
+            format!("/{}", uri_encode(key, false))
+        };
+        let query_string = canonical_query(query);
+        let mut url = format!("{}://{}{}", self.scheme, self.host, path);
+        if !query_string.is_empty() {
+            url.push('?');
+            url.push_str(&query_string);
+        }
+
+        let headers = match &self.config.credentials {
+            Some(credentials) => credentials.sign(
+                &self.config.region,
+                &amz_date(),
+                &SignableRequest {
+                    method,
+                    host: &self.host,
+                    path: &path,
+                    query,
+                    headers: &[],
+                    payload_hash: &hex(&Sha256::digest(body.unwrap_or(&[]))),
+                }),
+            None => Vec::new(),
+        };
+        let headers: Vec<(&str, &str)> = headers.iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+
+        self.transport.fetch(&url, method, &headers, body).await
+    }
+
+    async fn get_object(&self, key: &str) -> Result<Option<(Vec<u8>, Option<String>)>, Error> {
+        let resp = map_future_error_rust(self.request("GET", key, &[], None)).await?;
+        if resp.status() == 404 {
+            return Ok(None);
+        }
+        check_status(&resp, "GET")?;
+
+        let etag = resp.headers().get("ETag").unwrap_or(None);
+        let bytes = map_future_error_rust(response_bytes(&resp)).await?;
+
+        Ok(Some((bytes, etag)))
+    }
+
+    async fn head_object(&self, key: &str) -> Result<Option<Option<String>>, Error> {
+        let resp = map_future_error_rust(self.request("HEAD", key, &[], None)).await?;
+        if resp.status() == 404 {
+            return Ok(None);
+        }
+        check_status(&resp, "HEAD")?;
+
+        Ok(Some(resp.headers().get("ETag").unwrap_or(None)))
+    }
+
+    async fn put_object(&self, key: &str, body: &[u8]) -> Result<(), Error> {
+        let resp = map_future_error_rust(self.request("PUT", key, &[], Some(body))).await?;
+
+        check_status(&resp, "PUT")
+    }
+
+    async fn delete_object(&self, key: &str) -> Result<(), Error> {
+        let resp = map_future_error_rust(self.request("DELETE", key, &[], None)).await?;
+
+        check_status(&resp, "DELETE")
+    }
+
+    /// List objects with ListObjectsV2, following continuation tokens. If
+    /// `delimited`, keys below the next `/` are rolled up into common
+    /// prefixes.
+    async fn list_objects(&self, prefix: &str, delimited: bool) -> Result<ObjectListing, Error> {
+        let mut listing = ObjectListing::default();
+        let mut continuation_token: Option<String> = None;
+
+        loop {
+            let mut query = vec![("list-type", "2"), ("prefix", prefix)];
+            if delimited {
+                query.push(("delimiter", "/"));
+            }
+            if let Some(token) = &continuation_token {
+                query.push(("continuation-token", token));
+            }
+
+            let resp = map_future_error_rust(self.request("GET", "", &query, None)).await?;
+            check_status(&resp, "listing")?;
+            let xml = map_future_error_rust(response_text(&resp)).await?;
+
+            for contents in xml_elements(&xml, "Contents") {
+                listing.keys.extend(xml_elements(contents, "Key").first().map(|key| xml_unescape(key)));
+            }
+            for common in xml_elements(&xml, "CommonPrefixes") {
+                listing.common_prefixes.extend(
+                    xml_elements(common, "Prefix").first().map(|prefix| xml_unescape(prefix)));
+            }
+
+            let truncated = xml_elements(&xml, "IsTruncated").first() == Some(&"true");
+            continuation_token = xml_elements(&xml, "NextContinuationToken").first()
+                .filter(|_| truncated)
+                .map(|token| xml_unescape(token));
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+
+        Ok(listing)
+    }
+
+    /// Whether any object has the given key prefix, with a single request.
+    async fn has_objects(&self, prefix: &str) -> Result<bool, Error> {
+        let query = [("list-type", "2"), ("prefix", prefix), ("max-keys", "1")];
+        let resp = map_future_error_rust(self.request("GET", "", &query, None)).await?;
+        check_status(&resp, "listing")?;
+        let xml = map_future_error_rust(response_text(&resp)).await?;
+
+        Ok(!xml_elements(&xml, "Contents").is_empty())
+    }
+
+    async fn get_attributes(&self, path_name: &str) -> Result<serde_json::Value, Error> {
+        let key = self.object_key(&get_dataset_attributes_path(path_name));
+        match self.get_object(&key).await? {
+            Some((bytes, _)) => Ok(serde_json::from_slice(&bytes)?),
+            None => Ok(serde_json::Value::Object(serde_json::Map::new())),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl N5S3 {
+    /// Open a container from a configuration object with keys `endpoint`,
+    /// `region`, `bucket` and optionally `prefix`, `pathStyle` and
+    /// `credentials` (`accessKeyId`, `secretAccessKey` and optionally
+    /// `sessionToken`).
+    pub fn open(config: S3ConfigJson) -> PromiseN5S3 {
+        Self::open_config(config, Rc::new(GlobalFetch))
+    }
+
+    /// Open a container, making all requests through a JS callback as for
+    /// `N5HTTPFetch.open_with_transport`. Requests are signed before they are
+    /// passed to the callback.
+    pub fn open_with_transport(config: S3ConfigJson, transport: js_sys::Function) -> PromiseN5S3 {
+        Self::open_config(config, Rc::new(JsCallbackTransport::new(transport)))
+    }
+
+    /// Create a new container, or open an existing one.
+    pub fn create(config: S3ConfigJson) -> PromiseN5S3 {
+        let to_return = async move {
+            let config: S3Config = config.into_serde()?;
+            N5S3::create_writer_with_transport(config, Rc::new(GlobalFetch)).await
+                .map(JsValue::from)
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
+
+    fn open_config(config: S3ConfigJson, transport: Rc<dyn Transport>) -> PromiseN5S3 {
+        let to_return = async move {
+            let config: S3Config = config.into_serde()?;
+            N5S3::open_reader_with_transport(config, transport).await
+                .map(JsValue::from)
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
+}
+
+/// Delegations to expose N5PromiseReader, N5PromiseEtagReader,
+/// N5PromiseWriter and object dataset traits to WASM.
+#[wasm_bindgen]
+impl N5S3 {
+    pub fn get_version(&self) -> PromiseVersion {
+        N5PromiseReader::get_version(self)
+    }
+
+    pub fn get_dataset_attributes(&self, path_name: &str) -> PromiseDatasetAttributes {
+        N5PromiseReader::get_dataset_attributes(self, path_name)
+    }
+
+    pub fn exists(&self, path_name: &str) -> PromiseBool {
+        N5PromiseReader::exists(self, path_name)
+    }
+
+    pub fn dataset_exists(&self, path_name: &str) -> PromiseBool {
+        N5PromiseReader::dataset_exists(self, path_name)
+    }
+
+    pub fn read_block(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        grid_position: Coordinates,
+    ) -> PromiseDataBlock {
+        N5PromiseReader::read_block(self, path_name, data_attrs, grid_position)
+    }
+
+    pub fn iter_blocks(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        offset: Coordinates,
+        size: Coordinates,
+    ) -> Result<DataBlockAsyncIterator, JsValue> {
+        N5PromiseReader::iter_blocks(self, path_name, data_attrs, offset, size)
+    }
+
+    pub fn list(&self, path_name: &str) -> PromiseStrings {
+        N5PromiseReader::list(self, path_name)
+    }
+
+    pub fn list_attributes(&self, path_name: &str) -> PromiseAttributes {
+        N5PromiseReader::list_attributes(self, path_name)
+    }
+
+    pub fn block_etag(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        grid_position: Coordinates,
+    ) -> PromiseEtag {
+        N5PromiseEtagReader::block_etag(
+            self, path_name, data_attrs, grid_position)
+    }
+
+    pub fn read_block_with_etag(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        grid_position: Coordinates
+    ) -> PromiseDataBlock {
+        N5PromiseEtagReader::read_block_with_etag(
+            self, path_name, data_attrs, grid_position)
+    }
+
+    pub fn set_attributes(&self, path_name: &str, attributes: AttributesJson) -> PromiseVoid {
+        N5PromiseWriter::set_attributes(self, path_name, attributes)
+    }
+
+    pub fn create_group(&self, path_name: &str) -> PromiseVoid {
+        N5PromiseWriter::create_group(self, path_name)
+    }
+
+    pub fn create_dataset(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+    ) -> PromiseVoid {
+        N5PromiseWriter::create_dataset(self, path_name, data_attrs)
+    }
+
+    pub fn remove(&self, path_name: &str) -> PromiseVoid {
+        N5PromiseWriter::remove(self, path_name)
+    }
+
+    pub fn write_block(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        grid_position: Coordinates,
+        data: BlockData,
+    ) -> PromiseVoid {
+        N5PromiseWriter::write_block(self, path_name, data_attrs, grid_position, data)
+    }
+
+    pub fn delete_block(&self, path_name: &str, grid_position: Coordinates) -> PromiseBool {
+        N5PromiseWriter::delete_block(self, path_name, grid_position)
+    }
+
+    pub fn get_object_dataset_attributes(&self, path_name: &str) -> PromiseObjectDatasetAttributes {
+        N5PromiseObjectReader::get_object_dataset_attributes(self, path_name)
+    }
+
+    pub fn read_object_block(&self, path_name: &str, grid_position: Coordinates) -> PromiseBytes {
+        N5PromiseObjectReader::read_object_block(self, path_name, grid_position)
+    }
+
+    pub fn create_object_dataset(&self, path_name: &str, data_attrs: AttributesJson) -> PromiseVoid {
+        N5PromiseObjectWriter::create_object_dataset(self, path_name, data_attrs)
+    }
+
+    pub fn write_object_block(
+        &self,
+        path_name: &str,
+        grid_position: Coordinates,
+        data: Vec<u8>,
+    ) -> PromiseVoid {
+        N5PromiseObjectWriter::write_object_block(self, path_name, grid_position, data)
+    }
+}
+
+region_reader_delegations!(N5S3);
+
+#[async_trait(?Send)]
+impl N5AsyncReader for N5S3 {
+    async fn get_version(&self) -> Result<n5::Version, Error> {
+        let attr = self.get_attributes("").await?;
+        let ver = attr.get(n5::VERSION_ATTRIBUTE_KEY)
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "Not an N5 root"))?;
+
+        n5::Version::from_str(ver.as_str().unwrap_or(""))
+            .map_err(|error| Error::new(ErrorKind::InvalidData, format!("Invalid N5 version: {}", error)))
+    }
+
+    async fn get_dataset_attributes(&self, path_name: &str) -> Result<n5::DatasetAttributes, Error> {
+        let attr = self.get_attributes(path_name).await?;
+
+        Ok(serde_json::from_value(attr)?)
+    }
+
+    /// A group exists if it has attributes or any objects within it,
+    /// including a group marker.
+    async fn exists(&self, path_name: &str) -> Result<bool, Error> {
+        let key = self.object_key(&get_dataset_attributes_path(path_name));
+        if self.head_object(&key).await?.is_some() {
+            return Ok(true);
+        }
+
+        self.has_objects(&self.directory_prefix(path_name)).await
+    }
+
+    // Override the default N5AsyncReader impl to only request the attributes.
+    /// A dataset exists if it has attributes that are dataset attributes.
+    /// Failures to read them are errors rather than missing datasets.
+    async fn dataset_exists(&self, path_name: &str) -> Result<bool, Error> {
+        let attr = self.get_attributes(path_name).await?;
+
+        Ok(serde_json::from_value::<DatasetAttributes>(attr).is_ok())
+    }
+
+    async fn read_block_with_mode<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<Option<(VecDataBlock<T>, BlockMode)>, Error>
+        where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+            T: ReflectedType,
+    {
+        N5AsyncEtagReader::read_block_with_mode_and_etag(self, path_name, data_attrs, grid_position).await
+            .map(|maybe_block| maybe_block.map(|(block, mode, _etag)| (block, mode)))
+    }
+
+    /// Lists child prefixes, except for the numeric block directories of
+    /// datasets, as other backends list only groups and datasets.
+    async fn list(&self, path_name: &str) -> Result<Vec<String>, Error> {
+        let prefix = self.directory_prefix(path_name);
+        let listing = self.list_objects(&prefix, true).await?;
+
+        let mut children: Vec<String> = listing.common_prefixes.iter()
+            .map(|child| child[prefix.len()..].trim_end_matches('/'))
+            .filter(|name| !name.is_empty() && !name.bytes().all(|b| b.is_ascii_digit()))
+            .map(ToOwned::to_owned)
+            .collect();
+        children.sort();
+
+        Ok(children)
+    }
+
+    async fn list_attributes(&self, path_name: &str) -> Result<serde_json::Value, Error> {
+        self.get_attributes(path_name).await
+    }
+}
+
+#[async_trait(?Send)]
+impl N5AsyncEtagReader for N5S3 {
+    async fn block_etag(
+        &self,
+        path_name: &str,
+        _data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<Option<String>, Error> {
+        let key = self.object_key(&relative_block_path(path_name, &grid_position));
+
+        Ok(self.head_object(&key).await?.and_then(|etag| etag))
+    }
+
+    async fn read_block_with_mode_and_etag<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<Option<(VecDataBlock<T>, BlockMode, Option<String>)>, Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: ReflectedType,
+    {
+        let key = self.object_key(&relative_block_path(path_name, &grid_position));
+
+        match self.get_object(&key).await? {
+            Some((buff, etag)) => {
+                let (block, mode) = decode_block(&buff, data_attrs, grid_position)?;
+
+                Ok(Some((block, mode, etag)))
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl N5AsyncWriter for N5S3 {
+    async fn set_attributes(
+        &self,
+        path_name: &str,
+        attributes: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<(), Error> {
+        let mut existing = match self.get_attributes(path_name).await? {
+            serde_json::Value::Object(map) => map,
+            _ => serde_json::Map::new(),
+        };
+        merge_attributes(&mut existing, attributes);
+
+        let key = self.object_key(&get_dataset_attributes_path(path_name));
+        self.put_object(&key, &serde_json::to_vec(&existing)?).await
+    }
+
+    /// Groups are created as a marker object, which also makes all of their
+    /// parents exist.
+    async fn create_group(&self, path_name: &str) -> Result<(), Error> {
+        let prefix = self.directory_prefix(path_name);
+        if prefix.is_empty() {
+            return Ok(());
+        }
+
+        self.put_object(&prefix, &[]).await
+    }
+
+    async fn remove(&self, path_name: &str) -> Result<(), Error> {
+        // Removing the root would delete everything under the prefix, or
+        // the whole bucket without one.
+        if path_name.trim_matches('/').is_empty() {
+            return Err(Error::new(ErrorKind::InvalidInput, "Can not remove the container root"));
+        }
+
+        let listing = self.list_objects(&self.directory_prefix(path_name), false).await?;
+
+        futures::future::try_join_all(
+            listing.keys.iter().map(|key| self.delete_object(key))).await?;
+
+        Ok(())
+    }
+
+    async fn write_block<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        block: &VecDataBlock<T>,
+    ) -> Result<(), Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::WriteableDataBlock,
+                T: ReflectedType,
+    {
+        let key = self.object_key(&relative_block_path(path_name, block.get_grid_position()));
+        let bytes = encode_block(data_attrs, block)?;
+
+        self.put_object(&key, &bytes).await
+    }
+
+    /// S3 deletes succeed whether or not the object existed, so existence is
+    /// checked first.
+    async fn delete_block(
+        &self,
+        path_name: &str,
+        grid_position: &[u64],
+    ) -> Result<bool, Error> {
+        let key = self.object_key(&relative_block_path(path_name, grid_position));
+        if self.head_object(&key).await?.is_none() {
+            return Ok(false);
+        }
+
+        self.delete_object(&key).await?;
+
+        Ok(true)
+    }
+}
+
+#[async_trait(?Send)]
+impl N5AsyncObjectReader for N5S3 {
+    async fn read_block_bytes(
+        &self,
+        path_name: &str,
+        grid_position: &[u64],
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let key = self.object_key(&relative_block_path(path_name, grid_position));
+
+        Ok(self.get_object(&key).await?.map(|(buff, _etag)| buff))
+    }
+}
+
+#[async_trait(?Send)]
+impl N5AsyncObjectWriter for N5S3 {
+    async fn write_block_bytes(
+        &self,
+        path_name: &str,
+        grid_position: &[u64],
+        bytes: Vec<u8>,
+    ) -> Result<(), Error> {
+        let key = self.object_key(&relative_block_path(path_name, grid_position));
+
+        self.put_object(&key, &bytes).await
+    }
+}
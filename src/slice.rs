@@ -0,0 +1,263 @@
+//! Axis-aligned planes of 3D datasets, such as XY, XZ and YZ views, read
+//! from only the blocks intersecting the plane.
+
+use super::*;
+
+
+#[wasm_bindgen(typescript_custom_section)]
+const SLICE_TYPES: &str = r#"
+/** In-plane region of a slice, along its two in-plane axes in increasing order. */
+export interface SliceBounds {
+  offset: Coordinates;
+  size: Coordinates;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "SliceBounds")]
+    pub type SliceBoundsJson;
+
+    #[wasm_bindgen(typescript_type = "Promise<Slice>")]
+    pub type PromiseSlice;
+}
+
+/// In-plane region of a slice, along its two in-plane axes in increasing
+/// order.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SliceBounds {
+    pub offset: [u64; 2],
+    pub size: [u64; 2],
+}
+
+impl SliceBoundsJson {
+    fn to_bounds(&self) -> Result<SliceBounds, Error> {
+        let pair = |key: &str| -> Result<[u64; 2], Error> {
+            let coords: Coordinates = js_sys::Reflect::get(self, &key.into())
+                .map_err(convert_jsvalue_error)?
+                .unchecked_into();
+            match coords.to_vec()?[..] {
+                [u, v] => Ok([u, v]),
+                _ => Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Slice bounds `{}` must have 2 dimensions", key))),
+            }
+        };
+
+        Ok(SliceBounds {
+            offset: pair("offset")?,
+            size: pair("size")?,
+        })
+    }
+}
+
+/// A plane of a 3D dataset. Data is in column-major order of the in-plane
+/// axes, so rows run along the first in-plane axis and the row stride is
+/// the width.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Slice<T> {
+    /// The axis normal to the plane.
+    pub axis: usize,
+    /// The axes along the width and height of the plane.
+    pub plane_axes: [usize; 2],
+    /// The region read, clamped to the dataset.
+    pub bounds: SliceBounds,
+    pub data: Vec<T>,
+}
+
+impl<T> Slice<T> {
+    pub fn get_width(&self) -> usize {
+        self.bounds.size[0] as usize
+    }
+
+    pub fn get_height(&self) -> usize {
+        self.bounds.size[1] as usize
+    }
+}
+
+fn plane_axes(axis: usize) -> [usize; 2] {
+    match axis {
+        0 => [1, 2],
+        1 => [0, 2],
+        _ => [0, 1],
+    }
+}
+
+/// Reading planes of 3D datasets, for any reader.
+#[async_trait(?Send)]
+pub trait N5AsyncSliceReader: N5AsyncReader {
+    /// Read the plane at `index` along `axis` of a 3D dataset, within bounds
+    /// clamped to the dataset or else the whole plane. Voxels of missing
+    /// blocks are zero.
+    async fn read_slice<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        axis: usize,
+        index: u64,
+        bounds: Option<SliceBounds>,
+    ) -> Result<Slice<T>, Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: ReflectedType + Copy + Default,
+    {
+        let dimensions = data_attrs.get_dimensions();
+        if dimensions.len() != 3 {
+            return Err(Error::new(ErrorKind::InvalidInput, "Slices require a 3D dataset"));
+        }
+        if axis >= 3 || index >= dimensions[axis] {
+            return Err(Error::new(ErrorKind::InvalidInput, "Slice is outside the dataset"));
+        }
+
+        let plane_axes = plane_axes(axis);
+        let mut clamped = SliceBounds {
+            offset: [0, 0],
+            size: [dimensions[plane_axes[0]], dimensions[plane_axes[1]]],
+        };
+        if let Some(bounds) = bounds {
+            for (i, &plane_axis) in plane_axes.iter().enumerate() {
+                let dim = dimensions[plane_axis];
+                clamped.offset[i] = bounds.offset[i].min(dim);
+                clamped.size[i] = bounds.size[i].min(dim - clamped.offset[i]);
+            }
+        }
+
+        let mut offset = vec![0; 3];
+        let mut size = vec![0; 3];
+        offset[axis] = index;
+        size[axis] = 1;
+        for (i, &plane_axis) in plane_axes.iter().enumerate() {
+            offset[plane_axis] = clamped.offset[i];
+            size[plane_axis] = clamped.size[i];
+        }
+
+        let grid_positions = region_grid_positions(data_attrs, &offset, &size)?;
+        let blocks = futures::future::try_join_all(grid_positions.into_iter()
+            .map(|grid_position| self.read_block::<T>(path_name, data_attrs, grid_position.into())))
+            .await?;
+
+        let width = clamped.size[0] as usize;
+        let mut data = vec![T::default(); width * clamped.size[1] as usize];
+        for block in blocks.iter().flatten() {
+            for_each_in_region(data_attrs, block, &offset, &size, |coord, &value| {
+                let u = coord[plane_axes[0]] as usize;
+                let v = coord[plane_axes[1]] as usize;
+                data[u + width * v] = value;
+            });
+        }
+
+        Ok(Slice {
+            axis,
+            plane_axes,
+            bounds: clamped,
+            data,
+        })
+    }
+}
+
+impl<R: N5AsyncReader> N5AsyncSliceReader for R {}
+
+
+pub trait N5PromiseSliceReader {
+    /// Resolve to the `Slice` at `index` along `axis` of a 3D dataset.
+    fn read_slice(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        axis: usize,
+        index: Coordinate,
+        bounds: Option<SliceBoundsJson>,
+    ) -> PromiseSlice;
+}
+
+impl<T> N5PromiseSliceReader for T where T: N5AsyncReader + Clone + 'static {
+    fn read_slice(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        axis: usize,
+        index: Coordinate,
+        bounds: Option<SliceBoundsJson>,
+    ) -> PromiseSlice {
+
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let data_attrs = data_attrs.0.clone();
+        let data_type = data_attrs.get_data_type().clone();
+        let index = index.to_u64();
+        let bounds = bounds.map(|bounds| bounds.to_bounds()).transpose();
+
+        data_type_match! {
+            data_type,
+            future_to_promise(map_future_error_wasm(async move {
+                let slice = N5AsyncSliceReader::read_slice::<RsType>(
+                    &reader, &path_name, &data_attrs, axis, index?, bounds?).await?;
+                Ok(JsValue::from(JsSlice::new(data_attrs.get_data_type().clone(), &slice)))
+            })).unchecked_into()
+        }
+    }
+}
+
+
+/// A plane of a 3D dataset, as exposed to JS.
+#[wasm_bindgen(js_name = Slice)]
+pub struct JsSlice {
+    data_type: DataType,
+    axis: usize,
+    plane_axes: [usize; 2],
+    bounds: SliceBounds,
+    data: JsValue,
+}
+
+impl JsSlice {
+    fn new<T: VecBlockMonomorphizerReflection>(data_type: DataType, slice: &Slice<T>) -> JsSlice {
+        JsSlice {
+            data_type,
+            axis: slice.axis,
+            plane_axes: slice.plane_axes,
+            bounds: slice.bounds,
+            data: T::data_to_js(&slice.data),
+        }
+    }
+}
+
+#[wasm_bindgen(js_class = Slice)]
+impl JsSlice {
+    #[wasm_bindgen(getter)]
+    pub fn dtype(&self) -> DataTypeName {
+        JsValue::from(self.data_type.to_string()).unchecked_into()
+    }
+
+    /// The axis normal to the plane.
+    #[wasm_bindgen(getter)]
+    pub fn axis(&self) -> u32 {
+        self.axis as u32
+    }
+
+    /// The dataset axes along the width and height.
+    #[wasm_bindgen(getter, js_name = planeAxes)]
+    pub fn plane_axes(&self) -> Vec<u32> {
+        self.plane_axes.iter().map(|&axis| axis as u32).collect()
+    }
+
+    /// Offset of the slice along the in-plane axes.
+    #[wasm_bindgen(getter)]
+    pub fn offset(&self) -> Vec<u64> {
+        self.bounds.offset.to_vec()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> u32 {
+        self.bounds.size[0] as u32
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> u32 {
+        self.bounds.size[1] as u32
+    }
+
+    /// The data in rows of `width` elements, in the typed array for `dtype`.
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> BlockData {
+        self.data.clone().unchecked_into()
+    }
+}
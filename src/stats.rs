@@ -0,0 +1,353 @@
+//! Intensity statistics of blocks and regions, such as for setting display
+//! contrast automatically.
+
+use super::*;
+
+
+/// Most histogram bins, to bound the memory a caller can request.
+pub const MAX_HISTOGRAM_BINS: usize = 1 << 16;
+
+#[wasm_bindgen(typescript_custom_section)]
+const STATISTICS_TYPES: &str = r#"
+export interface StatisticsOptions {
+  /** Number of histogram bins, at most 65536. Defaults to 256. */
+  bins?: number;
+  /** Range of the histogram. Defaults to the minimum and maximum. */
+  range?: [number, number];
+  /** Percentiles, from 0 to 100, to estimate from the histogram. */
+  percentiles?: number[];
+  /** Read only this many blocks of a region, evenly spaced, for a faster estimate. */
+  sampleBlocks?: number;
+}
+
+export interface Statistics {
+  /** Number of values, excluding NaN. */
+  count: number;
+  min: number;
+  max: number;
+  mean: number;
+  /** Population standard deviation. */
+  std: number;
+  /** Counts of values in equal bins over `range`, excluding values outside it. */
+  histogram: { range: [number, number]; counts: number[] };
+  /** Estimates of the requested percentiles, in the same order. */
+  percentiles: number[];
+  /** Number of blocks read. */
+  blocks: number;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "StatisticsOptions")]
+    pub type StatisticsOptionsJson;
+
+    #[wasm_bindgen(typescript_type = "Promise<Statistics | undefined>")]
+    pub type PromiseStatistics;
+}
+
+fn default_bins() -> usize {
+    256
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StatisticsOptions {
+    /// Number of histogram bins.
+    pub bins: usize,
+    /// Range of the histogram, or else the minimum and maximum.
+    pub range: Option<[f64; 2]>,
+    /// Percentiles, from 0 to 100, to estimate from the histogram.
+    pub percentiles: Vec<f64>,
+    /// Read only this many blocks of a region, evenly spaced.
+    pub sample_blocks: Option<usize>,
+}
+
+impl Default for StatisticsOptions {
+    fn default() -> StatisticsOptions {
+        StatisticsOptions {
+            bins: default_bins(),
+            range: None,
+            percentiles: Vec::new(),
+            sample_blocks: None,
+        }
+    }
+}
+
+impl StatisticsOptions {
+    /// Check that the number of histogram bins is at most
+    /// `MAX_HISTOGRAM_BINS`.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.bins > MAX_HISTOGRAM_BINS {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Histograms are limited to {} bins, not {}", MAX_HISTOGRAM_BINS, self.bins)));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct Histogram {
+    pub range: [f64; 2],
+    pub counts: Vec<u64>,
+}
+
+impl Histogram {
+    /// Estimate a percentile, interpolating linearly within its bin.
+    fn percentile(&self, percentile: f64) -> f64 {
+        let total: u64 = self.counts.iter().sum();
+        let target = percentile.max(0.).min(100.) / 100. * total as f64;
+        let width = (self.range[1] - self.range[0]) / self.counts.len() as f64;
+
+        let mut below = 0.;
+        for (bin, &count) in self.counts.iter().enumerate() {
+            let count = count as f64;
+            if count > 0. && below + count >= target {
+                return self.range[0] + width * (bin as f64 + (target - below) / count);
+            }
+            below += count;
+        }
+
+        self.range[1]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct Statistics {
+    /// Number of values, excluding NaN.
+    pub count: u64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// Population standard deviation.
+    pub std: f64,
+    pub histogram: Histogram,
+    /// Estimates of the requested percentiles, in the same order.
+    pub percentiles: Vec<f64>,
+    /// Number of blocks read.
+    pub blocks: usize,
+}
+
+/// Compute statistics of the values passed by `visit`, which is called
+/// twice: once for the moments and range, and once for the histogram.
+/// Returns none if there are no values.
+fn compute_statistics(
+    visit: impl Fn(&mut dyn FnMut(f64)),
+    options: &StatisticsOptions,
+    blocks: usize,
+) -> Option<Statistics> {
+    let mut count = 0u64;
+    let mut mean = 0.;
+    let mut sum_squares = 0.;
+    let mut min = f64::INFINITY;
+    let mut max = f64::NEG_INFINITY;
+    visit(&mut |value| {
+        if value.is_nan() {
+            return;
+        }
+        // Welford's algorithm, which is stable for large counts.
+        count += 1;
+        let delta = value - mean;
+        mean += delta / count as f64;
+        sum_squares += delta * (value - mean);
+        min = min.min(value);
+        max = max.max(value);
+    });
+    if count == 0 {
+        return None;
+    }
+
+    let range = options.range.unwrap_or([min, max]);
+    let bins = options.bins.max(1);
+    let width = (range[1] - range[0]) / bins as f64;
+    let mut counts = vec![0; bins];
+    visit(&mut |value| {
+        if value.is_nan() || value < range[0] || value > range[1] {
+            return;
+        }
+        let bin = if width > 0. { ((value - range[0]) / width) as usize } else { 0 };
+        counts[bin.min(bins - 1)] += 1;
+    });
+
+    let histogram = Histogram { range, counts };
+    let percentiles = options.percentiles.iter()
+        .map(|&p| histogram.percentile(p).max(min).min(max))
+        .collect();
+
+    Some(Statistics {
+        count,
+        min,
+        max,
+        mean,
+        std: (sum_squares / count as f64).sqrt(),
+        histogram,
+        percentiles,
+        blocks,
+    })
+}
+
+/// Statistics of some data, such as of a block, or none if it is empty or
+/// all NaN.
+pub fn data_statistics<T: Numeric>(data: &[T], options: &StatisticsOptions) -> Option<Statistics> {
+    compute_statistics(
+        |visit| data.iter().for_each(|&value| visit(value.to_f64())),
+        options,
+        1)
+}
+
+/// Computing statistics of blocks and regions of datasets, for any reader.
+#[async_trait(?Send)]
+pub trait N5AsyncStatisticsReader: N5AsyncReader {
+    /// Statistics of a block, or none if it is missing or has no values.
+    async fn block_statistics<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: Vec<u64>,
+        options: &StatisticsOptions,
+    ) -> Result<Option<Statistics>, Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: Numeric,
+    {
+        let block = self.read_block::<T>(path_name, data_attrs, grid_position.into()).await?;
+        Ok(block.and_then(|block| data_statistics(block.get_data(), options)))
+    }
+
+    /// Statistics of a region of voxels, clamped to the dataset, or none if
+    /// it has no values. Missing blocks are excluded rather than counted as
+    /// zeros. With `sample_blocks`, only that many of the region's blocks
+    /// are read, evenly spaced in column-major order.
+    async fn region_statistics<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        offset: &[u64],
+        size: &[u64],
+        options: &StatisticsOptions,
+    ) -> Result<Option<Statistics>, Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: Numeric,
+    {
+        let mut grid_positions = region_grid_positions(data_attrs, offset, size)?;
+        if let Some(sample) = options.sample_blocks {
+            let total = grid_positions.len();
+            if sample < total {
+                grid_positions = (0..sample)
+                    .map(|i| grid_positions[i * total / sample].clone())
+                    .collect();
+            }
+        }
+
+        let blocks = futures::future::try_join_all(grid_positions.into_iter()
+            .map(|grid_position| self.read_block::<T>(path_name, data_attrs, grid_position.into())))
+            .await?;
+        let blocks: Vec<_> = blocks.into_iter().flatten().collect();
+
+        Ok(compute_statistics(
+            |visit| {
+                for block in &blocks {
+                    for_each_in_region(data_attrs, block, offset, size, |_, &value| visit(value.to_f64()));
+                }
+            },
+            options,
+            blocks.len()))
+    }
+}
+
+impl<R: N5AsyncReader> N5AsyncStatisticsReader for R {}
+
+
+fn statistics_options(options: Option<StatisticsOptionsJson>) -> Result<StatisticsOptions, Error> {
+    let options: StatisticsOptions = options
+        .map(|options| options.into_serde().map_err(Error::from))
+        .transpose()?
+        .unwrap_or_default();
+    options.validate()?;
+
+    Ok(options)
+}
+
+fn statistics_to_js(statistics: Option<Statistics>) -> JsValue {
+    statistics
+        .map(|statistics| JsValue::from_serde(&statistics).unwrap())
+        .unwrap_or(JsValue::UNDEFINED)
+}
+
+pub trait N5PromiseStatisticsReader {
+    /// Resolve to the `Statistics` of a block, or `undefined` if it is
+    /// missing or has no values.
+    fn block_statistics(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        grid_position: Coordinates,
+        options: Option<StatisticsOptionsJson>,
+    ) -> PromiseStatistics;
+
+    /// Resolve to the `Statistics` of a region of voxels, or `undefined` if
+    /// it has no values.
+    fn region_statistics(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        offset: Coordinates,
+        size: Coordinates,
+        options: Option<StatisticsOptionsJson>,
+    ) -> PromiseStatistics;
+}
+
+impl<T> N5PromiseStatisticsReader for T where T: N5AsyncReader + Clone + 'static {
+    fn block_statistics(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        grid_position: Coordinates,
+        options: Option<StatisticsOptionsJson>,
+    ) -> PromiseStatistics {
+
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let data_attrs = data_attrs.0.clone();
+        let data_type = data_attrs.get_data_type().clone();
+        let grid_position = grid_position.to_vec();
+        let options = statistics_options(options);
+
+        data_type_match! {
+            data_type,
+            future_to_promise(map_future_error_wasm(async move {
+                let statistics = N5AsyncStatisticsReader::block_statistics::<RsType>(
+                    &reader, &path_name, &data_attrs, grid_position?, &options?).await?;
+                Ok(statistics_to_js(statistics))
+            })).unchecked_into()
+        }
+    }
+
+    fn region_statistics(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        offset: Coordinates,
+        size: Coordinates,
+        options: Option<StatisticsOptionsJson>,
+    ) -> PromiseStatistics {
+
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let data_attrs = data_attrs.0.clone();
+        let data_type = data_attrs.get_data_type().clone();
+        let offset = offset.to_vec();
+        let size = size.to_vec();
+        let options = statistics_options(options);
+
+        data_type_match! {
+            data_type,
+            future_to_promise(map_future_error_wasm(async move {
+                let statistics = N5AsyncStatisticsReader::region_statistics::<RsType>(
+                    &reader, &path_name, &data_attrs, &offset?, &size?, &options?).await?;
+                Ok(statistics_to_js(statistics))
+            })).unchecked_into()
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Transports through which the HTTP backends make requests, so that the N5
+//! path logic and block decoding can be reused over an application's own
+//! networking layer.
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    Headers,
+    Request,
+    RequestInit,
+    RequestMode,
+    Response,
+};
+
+use super::*;
+
+
+#[async_trait(?Send)]
+pub trait Transport {
+    /// Make a request. The result may be a `Response` or any object with the
+    /// same interface (`ok`, `status`, `headers.get`, `json`, `text` and
+    /// `arrayBuffer`), since only those members are used.
+    async fn fetch(
+        &self,
+        url: &str,
+        method: &str,
+        headers: &[(&str, &str)],
+        body: Option<&[u8]>,
+    ) -> Result<Response, JsValue>;
+}
+
+
+/// Transport using the global `fetch`, whatever kind of global scope this is
+/// running in: window, dedicated/shared/service worker, Node.js or Deno. The
+/// global is inspected through reflection rather than `eval` so that this
+/// works under a strict content security policy.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GlobalFetch;
+
+#[async_trait(?Send)]
+impl Transport for GlobalFetch {
+    async fn fetch(
+        &self,
+        url: &str,
+        method: &str,
+        headers: &[(&str, &str)],
+        body: Option<&[u8]>,
+    ) -> Result<Response, JsValue> {
+        let mut request_options = RequestInit::new();
+        request_options.method(method);
+        request_options.mode(RequestMode::Cors);
+        if let Some(body) = body {
+            request_options.body(Some(&js_sys::Uint8Array::from(body)));
+        }
+        if !headers.is_empty() {
+            let request_headers = Headers::new()?;
+            for (name, value) in headers {
+                request_headers.append(name, value)?;
+            }
+            request_options.headers(&request_headers);
+        }
+
+        let req = Request::new_with_str_and_init(url, &request_options)?;
+
+        let global = js_sys::global();
+        let fetch = js_sys::Reflect::get(&global, &JsValue::from_str("fetch"))?
+            .dyn_into::<js_sys::Function>()
+            .map_err(|_| js_sys::Error::new("No global fetch function is available"))?;
+        let req_promise: Promise = fetch.call1(&global, &req)?.dyn_into()?;
+
+        let resp_value = JsFuture::from(req_promise).await?;
+        resp_value.dyn_into()
+            .map_err(|_| js_sys::Error::new("Global fetch did not resolve to a Response").into())
+    }
+}
+
+
+/// Transport delegating to a JS callback, called as
+/// `callback(url, method, headers, body)` with the headers as a plain object
+/// and the body as a `Uint8Array` or `undefined`, and returning a `Response`
+/// or response-like object or a promise of one.
+#[derive(Clone, Debug)]
+pub struct JsCallbackTransport {
+    callback: js_sys::Function,
+}
+
+impl JsCallbackTransport {
+    pub fn new(callback: js_sys::Function) -> Self {
+        JsCallbackTransport { callback }
+    }
+}
+
+#[async_trait(?Send)]
+impl Transport for JsCallbackTransport {
+    async fn fetch(
+        &self,
+        url: &str,
+        method: &str,
+        headers: &[(&str, &str)],
+        body: Option<&[u8]>,
+    ) -> Result<Response, JsValue> {
+        let request_headers = js_sys::Object::new();
+        for (name, value) in headers {
+            js_sys::Reflect::set(
+                &request_headers,
+                &JsValue::from_str(name),
+                &JsValue::from_str(value))?;
+        }
+
+        let args = js_sys::Array::of4(
+            &JsValue::from_str(url),
+            &JsValue::from_str(method),
+            &request_headers,
+            &body.map(|body| js_sys::Uint8Array::from(body).into()).unwrap_or(JsValue::UNDEFINED));
+        let resp_value = self.callback.apply(&JsValue::NULL, &args)?;
+        // `Promise.resolve` also accepts non-promise return values.
+        let resp_value = JsFuture::from(Promise::resolve(&resp_value)).await?;
+
+        Ok(resp_value.unchecked_into())
+    }
+}
@@ -0,0 +1,223 @@
+//! Precise TypeScript types for the JS API. wasm-bindgen declares every
+//! promise as `Promise<any>` and every `Vec<u64>` as `BigUint64Array`, so
+//! the JS-facing methods instead use these imported types, which are plain
+//! JS values declared with hand-written TypeScript types.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use wasm_bindgen_futures::JsFuture;
+
+use super::*;
+
+
+#[wasm_bindgen(typescript_custom_section)]
+const API_TYPES: &str = r#"
+/** Grid or voxel coordinates, as numbers or bigints. */
+export type Coordinates = ArrayLike<number | bigint> | Iterable<number | bigint>;
+
+/** Attributes of a group or dataset. */
+export type Attributes = { [key: string]: unknown };
+
+/** Dataset attributes as stored in `attributes.json`. */
+export interface DatasetAttributesJson extends Attributes {
+  dimensions: number[];
+  blockSize: number[];
+  dataType: DataType;
+  compression: { type: string; [key: string]: unknown };
+}
+
+/** Attributes of an object (opaque byte) dataset. */
+export interface ObjectDatasetAttributesJson extends Attributes {
+  dimensions: number[];
+  blockSize: number[];
+  dataType: "object";
+  compression: { type: string; [key: string]: unknown };
+}
+
+/** Where a block of an `N5Overlay` comes from. */
+export type BlockProvenance =
+  | { kind: "layer"; layer: number }
+  | { kind: "tombstone"; layer: number }
+  | { kind: "missing" };
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "number | bigint")]
+    pub type Coordinate;
+
+    #[wasm_bindgen(typescript_type = "Coordinates")]
+    pub type Coordinates;
+
+    #[wasm_bindgen(typescript_type = "Attributes")]
+    pub type AttributesJson;
+
+    #[wasm_bindgen(typescript_type = "DatasetAttributesJson")]
+    pub type DatasetAttributesJson;
+
+    #[wasm_bindgen(typescript_type = "AsyncIterableIterator<DataBlock>")]
+    pub type DataBlockAsyncIterator;
+
+    #[wasm_bindgen(typescript_type = "Promise<Version>")]
+    pub type PromiseVersion;
+
+    #[wasm_bindgen(typescript_type = "Promise<DatasetAttributes>")]
+    pub type PromiseDatasetAttributes;
+
+    #[wasm_bindgen(typescript_type = "Promise<boolean>")]
+    pub type PromiseBool;
+
+    #[wasm_bindgen(typescript_type = "Promise<DataBlock | undefined>")]
+    pub type PromiseDataBlock;
+
+    #[wasm_bindgen(typescript_type = "Promise<string[]>")]
+    pub type PromiseStrings;
+
+    #[wasm_bindgen(typescript_type = "Promise<Attributes>")]
+    pub type PromiseAttributes;
+
+    #[wasm_bindgen(typescript_type = "Promise<string | undefined>")]
+    pub type PromiseEtag;
+
+    #[wasm_bindgen(typescript_type = "Promise<void>")]
+    pub type PromiseVoid;
+
+    #[wasm_bindgen(typescript_type = "Promise<Uint8Array | undefined>")]
+    pub type PromiseBytes;
+
+    #[wasm_bindgen(typescript_type = "Promise<ObjectDatasetAttributesJson>")]
+    pub type PromiseObjectDatasetAttributes;
+
+    #[wasm_bindgen(typescript_type = "Promise<BlockProvenance>")]
+    pub type PromiseBlockProvenance;
+
+    #[wasm_bindgen(typescript_type = "Promise<N5FileSystem>")]
+    pub type PromiseN5FileSystem;
+
+    #[wasm_bindgen(typescript_type = "Promise<N5HTTPFetch>")]
+    pub type PromiseN5HTTPFetch;
+
+    #[wasm_bindgen(typescript_type = "Promise<N5S3>")]
+    pub type PromiseN5S3;
+
+    #[wasm_bindgen(typescript_type = "Promise<N5Zip>")]
+    pub type PromiseN5Zip;
+}
+
+impl From<Vec<u64>> for Coordinates {
+    fn from(coords: Vec<u64>) -> Self {
+        JsValue::from(js_sys::BigUint64Array::from(&coords[..])).unchecked_into()
+    }
+}
+
+impl Coordinate {
+    pub fn to_u64(&self) -> Result<u64, Error> {
+        coord_from_js(self)
+    }
+}
+
+impl Coordinates {
+    /// Convert to unsigned coordinates, accepting any array-like or
+    /// iterable of non-negative integer numbers or bigints.
+    pub fn to_vec(&self) -> Result<Vec<u64>, Error> {
+        if self.is_undefined() || self.is_null() {
+            return Err(Error::new(ErrorKind::InvalidInput, "Coordinates are missing"));
+        }
+
+        js_sys::Array::from(self).iter()
+            .map(|coord| coord_from_js(&coord))
+            .collect()
+    }
+}
+
+fn coord_from_js(value: &JsValue) -> Result<u64, Error> {
+    let invalid = || Error::new(
+        ErrorKind::InvalidInput,
+        format!("Coordinates must be non-negative integers, found {:?}", value));
+
+    if let Some(number) = value.as_f64() {
+        if number >= 0. && number.fract() == 0. && number <= u64::MAX as f64 {
+            Ok(number as u64)
+        } else {
+            Err(invalid())
+        }
+    } else if value.is_bigint() {
+        // Bigints are converted through their decimal representation, which
+        // for negative bigints does not parse.
+        value.unchecked_ref::<js_sys::BigInt>().to_string(10).ok()
+            .and_then(|digits| String::from(digits).parse().ok())
+            .ok_or_else(invalid)
+    } else {
+        Err(invalid())
+    }
+}
+
+/// Iterator over the existing blocks of a region, made async iterable for
+/// `for await` loops when returned to JS. Blocks are read one at a time.
+#[wasm_bindgen]
+pub struct BlockRegionIterator {
+    grid_positions: Rc<RefCell<VecDeque<Vec<u64>>>>,
+    read_block: Rc<dyn Fn(Vec<u64>) -> Promise>,
+}
+
+#[wasm_bindgen(inline_js = "
+export function make_async_iterable(iterator) {
+    iterator[Symbol.asyncIterator] = function () { return this; };
+    return iterator;
+}")]
+extern "C" {
+    fn make_async_iterable(iterator: JsValue) -> DataBlockAsyncIterator;
+}
+
+impl BlockRegionIterator {
+    pub(crate) fn new_js(
+        grid_positions: Vec<Vec<u64>>,
+        read_block: Rc<dyn Fn(Vec<u64>) -> Promise>,
+    ) -> DataBlockAsyncIterator {
+        make_async_iterable(JsValue::from(BlockRegionIterator {
+            grid_positions: Rc::new(RefCell::new(grid_positions.into())),
+            read_block,
+        }))
+    }
+}
+
+#[wasm_bindgen]
+impl BlockRegionIterator {
+    /// Resolve to an iterator result with the next existing block, skipping
+    /// missing blocks.
+    pub fn next(&self) -> Promise {
+        let grid_positions = self.grid_positions.clone();
+        let read_block = self.read_block.clone();
+        let to_return = async move {
+            loop {
+                let grid_position = grid_positions.borrow_mut().pop_front();
+                let result = js_sys::Object::new();
+                match grid_position {
+                    Some(grid_position) => {
+                        let block = JsFuture::from(read_block(grid_position)).await?;
+                        if block.is_undefined() {
+                            continue;
+                        }
+                        js_sys::Reflect::set(&result, &"value".into(), &block)?;
+                        js_sys::Reflect::set(&result, &"done".into(), &JsValue::FALSE)?;
+                    },
+                    None => {
+                        js_sys::Reflect::set(&result, &"done".into(), &JsValue::TRUE)?;
+                    },
+                }
+
+                return Ok::<_, JsValue>(result.into());
+            }
+        };
+
+        future_to_promise(to_return)
+    }
+
+    /// Number of grid positions not yet read, including missing blocks.
+    #[wasm_bindgen(getter)]
+    pub fn remaining(&self) -> usize {
+        self.grid_positions.borrow().len()
+    }
+}
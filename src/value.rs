@@ -0,0 +1,159 @@
+//! Values of individual voxels, such as under a cursor, without locating
+//! them within blocks by hand.
+
+use std::collections::BTreeSet;
+
+use super::*;
+
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "Coordinates[]")]
+    pub type CoordinatesList;
+
+    #[wasm_bindgen(typescript_type = "Promise<number | bigint | undefined>")]
+    pub type PromiseValue;
+
+    #[wasm_bindgen(typescript_type = "Promise<(number | bigint | undefined)[]>")]
+    pub type PromiseValues;
+}
+
+/// Reading voxel values, for any reader.
+#[async_trait(?Send)]
+pub trait N5AsyncValueReader: N5AsyncReader {
+    /// Read the value of the voxel at a coordinate, or none if its block is
+    /// missing.
+    async fn get_value<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        coord: &[u64],
+    ) -> Result<Option<T>, Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: ReflectedType + Copy,
+    {
+        let mut values = self.get_values::<T>(path_name, data_attrs, &[coord.to_vec()]).await?;
+        Ok(values.pop().flatten())
+    }
+
+    /// Read the values of the voxels at many coordinates, reading each block
+    /// holding any of them once. Values in missing blocks are none.
+    async fn get_values<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        coords: &[Vec<u64>],
+    ) -> Result<Vec<Option<T>>, Error>
+            where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+                T: ReflectedType + Copy,
+    {
+        let dimensions = data_attrs.get_dimensions();
+        for coord in coords {
+            if coord.len() != dimensions.len() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Coordinate has {} dimensions, but the dataset has {}", coord.len(), dimensions.len())));
+            }
+            if coord.iter().zip(dimensions).any(|(&c, &dim)| c >= dim) {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Coordinate {:?} is outside the dataset", coord)));
+            }
+        }
+
+        let block_size: Vec<u64> = data_attrs.get_block_size().iter().map(|&b| u64::from(b)).collect();
+        let grid_positions: BTreeSet<Vec<u64>> = coords.iter()
+            .map(|coord| VoxelLookup::<T>::grid_position(&block_size, coord))
+            .collect();
+        let lookup = VoxelLookup::<T>::read(self, path_name, data_attrs, grid_positions).await?;
+
+        Ok(coords.iter().map(|coord| lookup.get(coord)).collect())
+    }
+}
+
+impl<R: N5AsyncReader> N5AsyncValueReader for R {}
+
+
+/// Convert voxel values to JS numbers or, for 64-bit integers, bigints.
+fn values_to_js<T: VecBlockMonomorphizerReflection + Copy + Default>(values: &[Option<T>]) -> js_sys::Array {
+    let data: Vec<T> = values.iter().map(|value| value.unwrap_or_default()).collect();
+    let typed = T::data_to_js(&data);
+
+    values.iter()
+        .enumerate()
+        .map(|(i, value)| match value {
+            Some(_) => js_sys::Reflect::get_u32(&typed, i as u32).unwrap_or(JsValue::UNDEFINED),
+            None => JsValue::UNDEFINED,
+        })
+        .collect()
+}
+
+pub trait N5PromiseValueReader {
+    /// Resolve to the value of the voxel at a coordinate, or `undefined` if
+    /// its block is missing.
+    fn get_value(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        coord: Coordinates,
+    ) -> PromiseValue;
+
+    /// Resolve to the values of the voxels at many coordinates, `undefined`
+    /// where blocks are missing.
+    fn get_values(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        coords: CoordinatesList,
+    ) -> PromiseValues;
+}
+
+impl<T> N5PromiseValueReader for T where T: N5AsyncReader + Clone + 'static {
+    fn get_value(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        coord: Coordinates,
+    ) -> PromiseValue {
+
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let data_attrs = data_attrs.0.clone();
+        let data_type = data_attrs.get_data_type().clone();
+        let coord = coord.to_vec();
+
+        data_type_match! {
+            data_type,
+            future_to_promise(map_future_error_wasm(async move {
+                let value = N5AsyncValueReader::get_value::<RsType>(
+                    &reader, &path_name, &data_attrs, &coord?).await?;
+                Ok(values_to_js(&[value]).get(0))
+            })).unchecked_into()
+        }
+    }
+
+    fn get_values(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        coords: CoordinatesList,
+    ) -> PromiseValues {
+
+        let reader = self.clone();
+        let path_name = path_name.to_owned();
+        let data_attrs = data_attrs.0.clone();
+        let data_type = data_attrs.get_data_type().clone();
+        let coords: Result<Vec<Vec<u64>>, Error> = js_sys::Array::from(&coords).iter()
+            .map(|coord| coord.unchecked_into::<Coordinates>().to_vec())
+            .collect();
+
+        data_type_match! {
+            data_type,
+            future_to_promise(map_future_error_wasm(async move {
+                let values = N5AsyncValueReader::get_values::<RsType>(
+                    &reader, &path_name, &data_attrs, &coords?).await?;
+                Ok(JsValue::from(values_to_js(&values)))
+            })).unchecked_into()
+        }
+    }
+}
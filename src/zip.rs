@@ -0,0 +1,541 @@
+//! Read-only access to N5 containers packed into a single ZIP archive, read
+//! either with HTTP range requests or from a `Blob` (such as a `File`).
+//!
+//! Only the central directory is read up front. Entries must be stored or
+//! deflated and not encrypted; ZIP64 archives are supported.
+
+use std::cell::RefCell;
+use std::collections::{
+    BTreeSet,
+    HashMap,
+};
+use std::io::Read;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use js_sys::ArrayBuffer;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::Blob;
+
+use super::*;
+use crate::object::{
+    N5AsyncObjectReader,
+    N5PromiseObjectReader,
+};
+use crate::transport::{
+    GlobalFetch,
+    JsCallbackTransport,
+    Transport,
+};
+
+
+const EOCD_SIGNATURE: u32 = 0x0605_4b50;
+const EOCD_SIZE: u64 = 22;
+const ZIP64_EOCD_LOCATOR_SIGNATURE: u32 = 0x0706_4b50;
+const ZIP64_EOCD_LOCATOR_SIZE: u64 = 20;
+const ZIP64_EOCD_SIGNATURE: u32 = 0x0606_4b50;
+const ZIP64_EOCD_SIZE: u64 = 56;
+const ZIP64_EXTRA_FIELD_ID: u16 = 0x0001;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const CENTRAL_HEADER_SIZE: usize = 46;
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const LOCAL_HEADER_SIZE: u64 = 30;
+const MAX_COMMENT_SIZE: u64 = 0xFFFF;
+
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATED: u16 = 8;
+const FLAG_ENCRYPTED: u16 = 0x0001;
+
+/// Largest buffer preallocated for a decompressed entry, since the size in
+/// the central directory is not trusted.
+const MAX_PREALLOCATED_SIZE: u64 = 1 << 24;
+
+
+fn u16_at(buff: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([buff[offset], buff[offset + 1]])
+}
+
+fn u32_at(buff: &[u8], offset: usize) -> u32 {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(&buff[offset..offset + 4]);
+    u32::from_le_bytes(bytes)
+}
+
+fn u64_at(buff: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&buff[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+fn invalid_zip(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, format!("Invalid ZIP archive: {}", message))
+}
+
+
+/// The whole of an archive, once a server has ignored a range request for it.
+type WholeArchive = Rc<RefCell<Option<Rc<Vec<u8>>>>>;
+
+#[derive(Clone)]
+enum ZipSource {
+    Http(String, Rc<dyn Transport>, WholeArchive),
+    Blob(Blob),
+}
+
+impl ZipSource {
+    fn http(url: &str, transport: Rc<dyn Transport>) -> ZipSource {
+        ZipSource::Http(url.into(), transport, Rc::new(RefCell::new(None)))
+    }
+
+    async fn len(&self) -> Result<u64, Error> {
+        match self {
+            ZipSource::Http(url, transport, _) => {
+                let resp = map_future_error_rust(transport.fetch(url, "HEAD", &[], None)).await?;
+                resp.headers().get("Content-Length")
+                    .map_err(convert_jsvalue_error)?
+                    .and_then(|len| len.parse().ok())
+                    .ok_or_else(|| Error::new(
+                        ErrorKind::Other,
+                        "ZIP archive length is not available from Content-Length"))
+            },
+            ZipSource::Blob(blob) => Ok(blob.size() as u64),
+        }
+    }
+
+    async fn read_range(&self, offset: u64, length: u64) -> Result<Vec<u8>, Error> {
+        if length == 0 {
+            return Ok(Vec::new());
+        }
+
+        let buff = map_future_error_rust(self.fetch_range(offset, offset + length)).await?;
+        if buff.len() as u64 != length {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "Short read from ZIP archive"));
+        }
+
+        Ok(buff)
+    }
+
+    async fn fetch_range(&self, offset: u64, end: u64) -> Result<Vec<u8>, JsValue> {
+        match self {
+            ZipSource::Http(url, transport, whole) => {
+                let range_of = |archive: &[u8]| archive.get(offset as usize..end as usize).unwrap_or(&[]).to_vec();
+                let cached = whole.borrow().clone();
+                if let Some(cached) = cached {
+                    return Ok(range_of(&cached));
+                }
+
+                let range = format!("bytes={}-{}", offset, end - 1);
+                let resp = transport.fetch(url, "GET", &[("Range", range.as_str())], None).await?;
+                if !resp.ok() {
+                    return Err(js_sys::Error::new(
+                        &format!("Range request failed with status {}", resp.status())).into());
+                }
+                let buff = array_buffer_to_vec(JsFuture::from(resp.array_buffer()?).await?);
+
+                // Servers that ignore the range respond with the whole
+                // archive, which is kept rather than downloaded for every read.
+                if resp.status() == 200 {
+                    let range = range_of(&buff);
+                    whole.replace(Some(Rc::new(buff)));
+                    Ok(range)
+                } else {
+                    Ok(buff)
+                }
+            },
+            ZipSource::Blob(blob) => {
+                let slice = blob.slice_with_f64_and_f64(offset as f64, end as f64)?;
+                Ok(array_buffer_to_vec(JsFuture::from(slice.array_buffer()).await?))
+            },
+        }
+    }
+}
+
+fn array_buffer_to_vec(arrbuff_value: JsValue) -> Vec<u8> {
+    assert!(arrbuff_value.is_instance_of::<ArrayBuffer>());
+    js_sys::Uint8Array::new(&arrbuff_value).to_vec()
+}
+
+
+struct ZipEntry {
+    method: u16,
+    encrypted: bool,
+    compressed_size: u64,
+    uncompressed_size: u64,
+    local_header_offset: u64,
+}
+
+/// Parse the central directory into a map from entry path to entry. Directory
+/// entries (with a trailing `/`) are omitted.
+fn parse_central_directory(buff: &[u8], num_entries: u64) -> Result<HashMap<String, ZipEntry>, Error> {
+    let mut entries = HashMap::new();
+    let mut pos = 0;
+
+    for _ in 0..num_entries {
+        if buff.len() < pos + CENTRAL_HEADER_SIZE || u32_at(buff, pos) != CENTRAL_HEADER_SIGNATURE {
+            return Err(invalid_zip("bad central directory header"));
+        }
+        let flags = u16_at(buff, pos + 8);
+        let method = u16_at(buff, pos + 10);
+        let mut compressed_size = u64::from(u32_at(buff, pos + 20));
+        let mut uncompressed_size = u64::from(u32_at(buff, pos + 24));
+        let name_len = usize::from(u16_at(buff, pos + 28));
+        let extra_len = usize::from(u16_at(buff, pos + 30));
+        let comment_len = usize::from(u16_at(buff, pos + 32));
+        let mut local_header_offset = u64::from(u32_at(buff, pos + 42));
+
+        let name_start = pos + CENTRAL_HEADER_SIZE;
+        let extra_start = name_start + name_len;
+        let next = extra_start + extra_len + comment_len;
+        if buff.len() < next {
+            return Err(invalid_zip("truncated central directory"));
+        }
+        let name = String::from_utf8_lossy(&buff[name_start..extra_start]).into_owned();
+
+        // ZIP64 extra fields only hold the values whose 32-bit fields are
+        // saturated, in this order. Fields may not overrun the extra data.
+        let extra_end = extra_start + extra_len;
+        let mut extra_pos = extra_start;
+        while extra_pos + 4 <= extra_end {
+            let id = u16_at(buff, extra_pos);
+            let size = usize::from(u16_at(buff, extra_pos + 2));
+            if id == ZIP64_EXTRA_FIELD_ID {
+                let field_end = (extra_pos + 4 + size).min(extra_end);
+                let mut field_pos = extra_pos + 4;
+                for value in [&mut uncompressed_size, &mut compressed_size, &mut local_header_offset].iter_mut() {
+                    if **value == u64::from(std::u32::MAX) && field_pos + 8 <= field_end {
+                        **value = u64_at(buff, field_pos);
+                        field_pos += 8;
+                    }
+                }
+            }
+            extra_pos += 4 + size;
+        }
+
+        if !name.ends_with('/') {
+            entries.insert(name, ZipEntry {
+                method,
+                encrypted: flags & FLAG_ENCRYPTED != 0,
+                compressed_size,
+                uncompressed_size,
+                local_header_offset,
+            });
+        }
+
+        pos = next;
+    }
+
+    Ok(entries)
+}
+
+
+/// N5 container packed into a ZIP archive.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct N5Zip {
+    source: ZipSource,
+    entries: Rc<HashMap<String, ZipEntry>>,
+    /// Path of the container root within the archive, either empty or ending
+    /// with `/`.
+    root: String,
+}
+
+impl N5Zip {
+    async fn read_archive(source: ZipSource) -> Result<N5Zip, Error> {
+        let len = source.len().await?;
+        if len < EOCD_SIZE {
+            return Err(invalid_zip("too short"));
+        }
+
+        // The end of central directory record is followed only by a comment,
+        // so search backwards for its signature.
+        let tail_len = len.min(EOCD_SIZE + MAX_COMMENT_SIZE + ZIP64_EOCD_LOCATOR_SIZE);
+        let tail_offset = len - tail_len;
+        let tail = source.read_range(tail_offset, tail_len).await?;
+        let eocd_pos = (0..=(tail.len() - EOCD_SIZE as usize)).rev()
+            .find(|&pos| u32_at(&tail, pos) == EOCD_SIGNATURE)
+            .ok_or_else(|| invalid_zip("no end of central directory record"))?;
+
+        let mut num_entries = u64::from(u16_at(&tail, eocd_pos + 10));
+        let mut cd_size = u64::from(u32_at(&tail, eocd_pos + 12));
+        let mut cd_offset = u64::from(u32_at(&tail, eocd_pos + 16));
+
+        let locator_pos = eocd_pos.checked_sub(ZIP64_EOCD_LOCATOR_SIZE as usize);
+        if let Some(locator_pos) = locator_pos {
+            if u32_at(&tail, locator_pos) == ZIP64_EOCD_LOCATOR_SIGNATURE {
+                let zip64_eocd_offset = u64_at(&tail, locator_pos + 8);
+                let zip64_eocd = source.read_range(zip64_eocd_offset, ZIP64_EOCD_SIZE).await?;
+                if u32_at(&zip64_eocd, 0) != ZIP64_EOCD_SIGNATURE {
+                    return Err(invalid_zip("bad ZIP64 end of central directory record"));
+                }
+                num_entries = u64_at(&zip64_eocd, 32);
+                cd_size = u64_at(&zip64_eocd, 40);
+                cd_offset = u64_at(&zip64_eocd, 48);
+            }
+        }
+
+        let central_directory = source.read_range(cd_offset, cd_size).await?;
+        let entries = parse_central_directory(&central_directory, num_entries)?;
+
+        // Archives are often created by zipping the container directory
+        // itself, so accept a single top-level directory as the root.
+        let root = if entries.contains_key(ATTRIBUTES_FILE) {
+            String::new()
+        } else {
+            let roots: BTreeSet<&str> = entries.keys()
+                .filter_map(|name| name.find('/').map(|i| &name[..=i]))
+                .collect();
+            match roots.iter().next() {
+                Some(root) if roots.len() == 1 => (*root).to_owned(),
+                _ => String::new(),
+            }
+        };
+
+        Ok(N5Zip {
+            source,
+            entries: Rc::new(entries),
+            root,
+        })
+    }
+
+    async fn open_reader_source(source: ZipSource) -> Result<N5Zip, Error> {
+        let reader = Self::read_archive(source).await?;
+        let version = N5AsyncReader::get_version(&reader).await?;
+
+        if !n5::is_version_compatible(&n5::VERSION, &version) {
+            return Err(Error::new(ErrorKind::Other, "TODO: Incompatible version"))
+        }
+
+        Ok(reader)
+    }
+
+    /// Open an archive over HTTP from Rust, making all requests through
+    /// `transport` and checking that its N5 version is compatible.
+    pub async fn open_url_reader(url: &str, transport: Rc<dyn Transport>) -> Result<N5Zip, Error> {
+        Self::open_reader_source(ZipSource::http(url, transport)).await
+    }
+
+    /// Open an archive from a `Blob` from Rust, checking that its N5 version
+    /// is compatible.
+    pub async fn open_blob_reader(blob: Blob) -> Result<N5Zip, Error> {
+        Self::open_reader_source(ZipSource::Blob(blob)).await
+    }
+
+    fn open_source(source: ZipSource) -> PromiseN5Zip {
+        let to_return = async move {
+            Self::open_reader_source(source).await.map(JsValue::from)
+        };
+
+        future_to_promise(map_future_error_wasm(to_return)).unchecked_into()
+    }
+
+    fn entry_name(&self, path_name: &str) -> String {
+        format!("{}{}", self.root, path_name.trim_matches('/'))
+    }
+
+    /// Read and decompress an entry, or `None` if it is not in the archive.
+    async fn read_entry(&self, path_name: &str) -> Result<Option<Vec<u8>>, Error> {
+        let entry = match self.entries.get(&self.entry_name(path_name)) {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        if entry.encrypted {
+            return Err(Error::new(ErrorKind::InvalidData, "Encrypted ZIP entries are not supported"));
+        }
+
+        let header = self.source.read_range(entry.local_header_offset, LOCAL_HEADER_SIZE).await?;
+        if u32_at(&header, 0) != LOCAL_HEADER_SIGNATURE {
+            return Err(invalid_zip("bad local file header"));
+        }
+        let data_offset = entry.local_header_offset
+            + LOCAL_HEADER_SIZE
+            + u64::from(u16_at(&header, 26))
+            + u64::from(u16_at(&header, 28));
+        let compressed = self.source.read_range(data_offset, entry.compressed_size).await?;
+
+        match entry.method {
+            METHOD_STORED => Ok(Some(compressed)),
+            METHOD_DEFLATED => {
+                // Decompress no more than the declared size, so that an entry
+                // can not expand without bound.
+                let mut buff = Vec::with_capacity(entry.uncompressed_size.min(MAX_PREALLOCATED_SIZE) as usize);
+                flate2::read::DeflateDecoder::new(&compressed[..])
+                    .take(entry.uncompressed_size.saturating_add(1))
+                    .read_to_end(&mut buff)?;
+                if buff.len() as u64 != entry.uncompressed_size {
+                    return Err(invalid_zip("entry size does not match the central directory"));
+                }
+                Ok(Some(buff))
+            },
+            method => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Unsupported ZIP compression method: {}", method))),
+        }
+    }
+
+    async fn get_attributes(&self, path_name: &str) -> Result<serde_json::Value, Error> {
+        let path = get_dataset_attributes_path(path_name);
+        match self.read_entry(&path).await? {
+            Some(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            None => Ok(serde_json::Value::Object(serde_json::Map::new())),
+        }
+    }
+
+    fn directory_prefix(&self, path_name: &str) -> String {
+        let name = self.entry_name(path_name);
+        if name.is_empty() || name.ends_with('/') {
+            name
+        } else {
+            name + "/"
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl N5Zip {
+    /// Open an archive over HTTP. The server must report `Content-Length` for
+    /// `HEAD` requests, and should support range requests, without which the
+    /// whole archive is downloaded and kept in memory.
+    pub fn open_url(url: &str) -> PromiseN5Zip {
+        Self::open_source(ZipSource::http(url, Rc::new(GlobalFetch)))
+    }
+
+    /// Open an archive over HTTP, making all requests through a JS callback
+    /// as for `N5HTTPFetch.open_with_transport`.
+    pub fn open_url_with_transport(url: &str, transport: js_sys::Function) -> PromiseN5Zip {
+        let transport = Rc::new(JsCallbackTransport::new(transport));
+        Self::open_source(ZipSource::http(url, transport))
+    }
+
+    /// Open an archive from a `Blob` or `File`.
+    pub fn open_blob(blob: Blob) -> PromiseN5Zip {
+        Self::open_source(ZipSource::Blob(blob))
+    }
+}
+
+/// Delegations to expose N5PromiseReader and N5PromiseObjectReader traits to
+/// WASM.
+#[wasm_bindgen]
+impl N5Zip {
+    pub fn get_version(&self) -> PromiseVersion {
+        N5PromiseReader::get_version(self)
+    }
+
+    pub fn get_dataset_attributes(&self, path_name: &str) -> PromiseDatasetAttributes {
+        N5PromiseReader::get_dataset_attributes(self, path_name)
+    }
+
+    pub fn exists(&self, path_name: &str) -> PromiseBool {
+        N5PromiseReader::exists(self, path_name)
+    }
+
+    pub fn dataset_exists(&self, path_name: &str) -> PromiseBool {
+        N5PromiseReader::dataset_exists(self, path_name)
+    }
+
+    pub fn read_block(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        grid_position: Coordinates,
+    ) -> PromiseDataBlock {
+        N5PromiseReader::read_block(self, path_name, data_attrs, grid_position)
+    }
+
+    pub fn iter_blocks(
+        &self,
+        path_name: &str,
+        data_attrs: &wrapped::DatasetAttributes,
+        offset: Coordinates,
+        size: Coordinates,
+    ) -> Result<DataBlockAsyncIterator, JsValue> {
+        N5PromiseReader::iter_blocks(self, path_name, data_attrs, offset, size)
+    }
+
+    pub fn list(&self, path_name: &str) -> PromiseStrings {
+        N5PromiseReader::list(self, path_name)
+    }
+
+    pub fn list_attributes(&self, path_name: &str) -> PromiseAttributes {
+        N5PromiseReader::list_attributes(self, path_name)
+    }
+
+    pub fn get_object_dataset_attributes(&self, path_name: &str) -> PromiseObjectDatasetAttributes {
+        N5PromiseObjectReader::get_object_dataset_attributes(self, path_name)
+    }
+
+    pub fn read_object_block(&self, path_name: &str, grid_position: Coordinates) -> PromiseBytes {
+        N5PromiseObjectReader::read_object_block(self, path_name, grid_position)
+    }
+}
+
+region_reader_delegations!(N5Zip);
+
+#[async_trait(?Send)]
+impl N5AsyncReader for N5Zip {
+    async fn get_version(&self) -> Result<n5::Version, Error> {
+        let attr = self.get_attributes("").await?;
+        let ver = attr.get(n5::VERSION_ATTRIBUTE_KEY)
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "Not an N5 root"))?;
+
+        n5::Version::from_str(ver.as_str().unwrap_or(""))
+            .map_err(|error| Error::new(ErrorKind::InvalidData, format!("Invalid N5 version: {}", error)))
+    }
+
+    async fn get_dataset_attributes(&self, path_name: &str) -> Result<n5::DatasetAttributes, Error> {
+        let path = get_dataset_attributes_path(path_name);
+        let bytes = self.read_entry(&path).await?
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "Dataset attributes not found"))?;
+
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    async fn exists(&self, path_name: &str) -> Result<bool, Error> {
+        let prefix = self.directory_prefix(path_name);
+
+        Ok(self.entries.keys().any(|name| name.starts_with(&prefix)))
+    }
+
+    async fn read_block_with_mode<T>(
+        &self,
+        path_name: &str,
+        data_attrs: &DatasetAttributes,
+        grid_position: GridCoord,
+    ) -> Result<Option<(VecDataBlock<T>, BlockMode)>, Error>
+        where VecDataBlock<T>: DataBlock<T> + n5::ReadableDataBlock,
+            T: ReflectedType,
+    {
+        let block_path = relative_block_path(path_name, &grid_position);
+
+        match self.read_entry(&block_path).await? {
+            Some(buff) => Ok(Some(decode_block(&buff, data_attrs, grid_position)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn list(&self, path_name: &str) -> Result<Vec<String>, Error> {
+        let prefix = self.directory_prefix(path_name);
+
+        // Only children with entries nested below them are directories.
+        let children: BTreeSet<&str> = self.entries.keys()
+            .filter_map(|name| name.strip_prefix(prefix.as_str()))
+            .filter_map(|rest| rest.find('/').map(|i| &rest[..i]))
+            .collect();
+
+        Ok(children.into_iter().map(ToOwned::to_owned).collect())
+    }
+
+    async fn list_attributes(&self, path_name: &str) -> Result<serde_json::Value, Error> {
+        self.get_attributes(path_name).await
+    }
+}
+
+#[async_trait(?Send)]
+impl N5AsyncObjectReader for N5Zip {
+    async fn read_block_bytes(
+        &self,
+        path_name: &str,
+        grid_position: &[u64],
+    ) -> Result<Option<Vec<u8>>, Error> {
+        self.read_entry(&relative_block_path(path_name, grid_position)).await
+    }
+}
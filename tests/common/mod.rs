@@ -0,0 +1,223 @@
+//! Fixtures shared by the test suites: a mock global `fetch` serving an
+//! in-memory N5 container over fake HTTP, and in-memory datasets.
+
+#![allow(dead_code)]
+
+use js_sys::{
+    Function,
+    Map,
+    Object,
+    Reflect,
+    Uint8Array,
+};
+use n5::prelude::*;
+use n5_wasm::N5AsyncWriter;
+use n5_wasm::in_memory::N5InMemory;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+
+pub const BASE_URL: &str = "http://n5.mock";
+
+/// Body of a JS function taking the map of fixture files and returning a
+/// `fetch` implementation serving them.
+const MOCK_FETCH_SOURCE: &str = r#"
+    return async function (request) {
+        const file = files.get(request.url);
+        if (file === undefined) {
+            return new Response(null, { status: 404 });
+        }
+
+        if (file.status !== undefined) {
+            return new Response(null, { status: file.status });
+        }
+
+        const headers = new Headers();
+        if (file.etag !== undefined) {
+            headers.set('ETag', file.etag);
+        }
+        const body = request.method === 'HEAD' ? null : file.body;
+
+        return new Response(body, { status: 200, headers });
+    };
+"#;
+
+/// Container of fixture files served by a mock `fetch` at `BASE_URL`.
+pub struct MockContainer {
+    files: Map,
+}
+
+impl MockContainer {
+    /// Create a container whose root has the given N5 version.
+    pub fn new(version: &str) -> Self {
+        let container = MockContainer { files: Map::new() };
+        container.add_json("attributes.json", &serde_json::json!({ "n5": version }));
+
+        container
+    }
+
+    /// Create a container with the version of the N5 crate.
+    pub fn compatible() -> Self {
+        Self::new(&n5::VERSION.to_string())
+    }
+
+    pub fn add_file(&self, path: &str, body: &[u8], etag: Option<&str>) {
+        self.add_url(&format!("{}/{}", BASE_URL, path), body, etag);
+    }
+
+    /// Serve a file at an absolute URL, such as an object store endpoint.
+    pub fn add_url(&self, url: &str, body: &[u8], etag: Option<&str>) {
+        let file = Object::new();
+        Reflect::set(&file, &"body".into(), &Uint8Array::from(body)).unwrap();
+        if let Some(etag) = etag {
+            Reflect::set(&file, &"etag".into(), &etag.into()).unwrap();
+        }
+
+        self.files.set(&url.into(), &file);
+    }
+
+    /// Respond to requests for a path with an error status.
+    pub fn add_error(&self, path: &str, status: u16) {
+        self.add_url_error(&format!("{}/{}", BASE_URL, path), status);
+    }
+
+    /// Respond to requests for an absolute URL with an error status.
+    pub fn add_url_error(&self, url: &str, status: u16) {
+        let file = Object::new();
+        Reflect::set(&file, &"status".into(), &status.into()).unwrap();
+
+        self.files.set(&url.into(), &file);
+    }
+
+    pub fn add_json(&self, path: &str, value: &serde_json::Value) {
+        self.add_file(path, &serde_json::to_vec(value).unwrap(), None);
+    }
+
+    pub fn add_dataset(&self, path: &str, data_attrs: &DatasetAttributes) {
+        self.add_json(
+            &format!("{}/attributes.json", path),
+            &serde_json::to_value(data_attrs).unwrap());
+    }
+
+    pub fn add_block<T>(
+        &self,
+        path: &str,
+        data_attrs: &DatasetAttributes,
+        block: &VecDataBlock<T>,
+        etag: Option<&str>,
+    ) where VecDataBlock<T>: DataBlock<T> + n5::WriteableDataBlock,
+            T: ReflectedType,
+    {
+        let mut buffer = Vec::new();
+        <n5::DefaultBlock as n5::DefaultBlockWriter<T, _, _>>::write_block(
+            &mut buffer,
+            data_attrs,
+            block).unwrap();
+
+        let mut block_path = path.to_owned();
+        for coord in block.get_grid_position() {
+            block_path.push_str(&format!("/{}", coord));
+        }
+
+        self.add_file(&block_path, &buffer, etag);
+    }
+
+    /// Replace the global `fetch` with one serving this container.
+    pub fn install(&self) {
+        let make_fetch = Function::new_with_args("files", MOCK_FETCH_SOURCE);
+        let fetch = make_fetch.call1(&JsValue::NULL, &self.files).unwrap();
+        Reflect::set(&js_sys::global(), &"fetch".into(), &fetch).unwrap();
+    }
+}
+
+/// Dataset attributes with raw compression for a JSON data type name.
+pub fn dataset_attributes(
+    dimensions: &[u64],
+    block_size: &[u32],
+    data_type: &str,
+) -> DatasetAttributes {
+    serde_json::from_value(serde_json::json!({
+        "dimensions": dimensions,
+        "blockSize": block_size,
+        "dataType": data_type,
+        "compression": { "type": "raw" },
+    })).unwrap()
+}
+
+/// Positions in a grid of the given shape, with the first axis varying
+/// fastest.
+fn column_major(shape: &[u64]) -> Vec<Vec<u64>> {
+    let mut positions = vec![vec![]];
+    for &extent in shape.iter().rev() {
+        positions = positions.into_iter()
+            .flat_map(|outer| (0..extent).map(move |coord| {
+                let mut position = vec![coord];
+                position.extend(&outer);
+                position
+            }))
+            .collect();
+    }
+
+    positions
+}
+
+/// An in-memory container with a dataset at `path` whose voxels are given by
+/// `voxel` of their coordinates. Every block is written except those at
+/// `missing_blocks`.
+pub async fn in_memory_dataset<T>(
+    path: &str,
+    data_attrs: DatasetAttributes,
+    missing_blocks: &[&[u64]],
+    voxel: impl Fn(&[u64]) -> T,
+) -> (N5InMemory, DatasetAttributes)
+        where VecDataBlock<T>: DataBlock<T> + n5::WriteableDataBlock,
+            T: ReflectedType,
+{
+    let container = N5InMemory::new();
+    N5AsyncWriter::create_dataset(&container, path, &data_attrs).await.unwrap();
+
+    let block_size: Vec<u64> = data_attrs.get_block_size().iter().map(|&b| u64::from(b)).collect();
+    let grid_extent: Vec<u64> = data_attrs.get_dimensions().iter()
+        .zip(&block_size)
+        .map(|(&dim, &size)| (dim + size - 1) / size)
+        .collect();
+    for grid_position in column_major(&grid_extent) {
+        if missing_blocks.contains(&grid_position.as_slice()) {
+            continue;
+        }
+        let offset: Vec<u64> = grid_position.iter().zip(&block_size).map(|(&g, &s)| g * s).collect();
+        let size: Vec<u64> = offset.iter()
+            .zip(data_attrs.get_dimensions())
+            .zip(&block_size)
+            .map(|((&o, &dim), &s)| (dim - o).min(s))
+            .collect();
+        let data = column_major(&size).iter()
+            .map(|local| {
+                let coords: Vec<u64> = local.iter().zip(&offset).map(|(&l, &o)| l + o).collect();
+                voxel(&coords)
+            })
+            .collect();
+        let size: Vec<u32> = size.iter().map(|&s| s as u32).collect();
+        let block = VecDataBlock::<T>::new(size.into(), grid_position.into(), data);
+        N5AsyncWriter::write_block(&container, path, &data_attrs, &block).await.unwrap();
+    }
+
+    (container, data_attrs)
+}
+
+/// Await a promise returned by the WASM API.
+pub async fn await_promise<P: JsCast>(promise: P) -> Result<JsValue, JsValue> {
+    JsFuture::from(promise.unchecked_into::<js_sys::Promise>()).await
+}
+
+/// Call a zero-argument method of a JS object, such as a wrapped block.
+pub fn call_method(object: &JsValue, name: &str) -> JsValue {
+    let method: Function = Reflect::get(object, &name.into()).unwrap().into();
+    method.call0(object).unwrap()
+}
+
+/// Get a property of a JS object, such as a getter of a wrapped block.
+pub fn get_property(object: &JsValue, name: &str) -> JsValue {
+    Reflect::get(object, &name.into()).unwrap()
+}
@@ -0,0 +1,62 @@
+use wasm_bindgen_test::*;
+
+use n5::prelude::*;
+use n5_wasm::downsample::{
+    Downsampling,
+    N5AsyncDownsampleReader,
+};
+use n5_wasm::in_memory::N5InMemory;
+
+mod common;
+use common::*;
+
+
+/// A 5x2 dataset in 2x2 blocks:
+///
+/// ```text
+/// 1 3 | 5 5 | 9
+/// 2 2 | 6 8 | 9
+/// ```
+async fn dataset() -> (N5InMemory, DatasetAttributes) {
+    let rows = [[1u16, 3, 5, 5, 9], [2, 2, 6, 8, 9]];
+    in_memory_dataset(
+        "full",
+        dataset_attributes(&[5, 2], &[2, 2], "uint16"),
+        &[],
+        |p| rows[p[1] as usize][p[0] as usize],
+    ).await
+}
+
+#[wasm_bindgen_test]
+async fn test_read_region_downsampled() {
+    let (container, data_attrs) = dataset().await;
+
+    let (container, data_attrs) = (&container, &data_attrs);
+    let read = move |method| N5AsyncDownsampleReader::read_region_downsampled::<u16>(
+        container, "full", data_attrs, &[0, 0], &[5, 2], &[2, 2], method);
+
+    let mean = read(Downsampling::Mean).await.unwrap();
+    assert_eq!(mean.size, vec![3, 1]);
+    assert_eq!(mean.data, vec![2, 6, 9]);
+    assert_eq!(read(Downsampling::Mode).await.unwrap().data, vec![2, 5, 9]);
+    assert_eq!(read(Downsampling::Max).await.unwrap().data, vec![3, 8, 9]);
+    assert_eq!(read(Downsampling::Stride).await.unwrap().data, vec![1, 5, 9]);
+}
+
+#[wasm_bindgen_test]
+async fn test_read_region_downsampled_offset() {
+    let (container, data_attrs) = dataset().await;
+
+    // Cells start at the region's offset and are clamped to the dataset.
+    let region = N5AsyncDownsampleReader::read_region_downsampled::<u16>(
+            &container, "full", &data_attrs, &[1, 0], &[10, 1], &[3, 1], Downsampling::Max)
+        .await
+        .unwrap();
+    assert_eq!(region.size, vec![2, 1]);
+    assert_eq!(region.data, vec![5, 9]);
+
+    assert!(N5AsyncDownsampleReader::read_region_downsampled::<u16>(
+            &container, "full", &data_attrs, &[0, 0], &[5, 2], &[0, 1], Downsampling::Max)
+        .await
+        .is_err());
+}
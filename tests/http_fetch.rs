@@ -0,0 +1,389 @@
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+use n5::prelude::*;
+use n5_wasm::{
+    N5AsyncEtagReader,
+    N5AsyncReader,
+    N5PromiseReader,
+};
+use n5_wasm::http_fetch::N5HTTPFetch;
+
+mod common;
+use common::*;
+
+
+async fn open_volume() -> (N5HTTPFetch, DatasetAttributes) {
+    let container = MockContainer::compatible();
+    let data_attrs = dataset_attributes(&[4, 3], &[2, 2], "uint16");
+    container.add_dataset("volume", &data_attrs);
+    let block = VecDataBlock::<u16>::new(
+        vec![2, 2].into(),
+        vec![0, 1].into(),
+        vec![1, 2, 3, 4]);
+    container.add_block("volume", &data_attrs, &block, Some("\"abc\""));
+    container.install();
+
+    (N5HTTPFetch::open_reader(BASE_URL).await.unwrap(), data_attrs)
+}
+
+#[wasm_bindgen_test]
+async fn test_open() {
+    MockContainer::compatible().install();
+
+    let reader = await_promise(N5HTTPFetch::open(BASE_URL)).await;
+    assert!(reader.is_ok());
+}
+
+#[wasm_bindgen_test]
+async fn test_open_incompatible_version() {
+    MockContainer::new("0.1.0").install();
+
+    assert!(await_promise(N5HTTPFetch::open(BASE_URL)).await.is_err());
+    assert!(N5HTTPFetch::open_reader(BASE_URL).await.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_open_missing_root() {
+    MockContainer::compatible().install();
+
+    assert!(N5HTTPFetch::open_reader(&format!("{}/missing", BASE_URL)).await.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_exists() {
+    let (reader, _) = open_volume().await;
+
+    assert!(N5AsyncReader::dataset_exists(&reader, "volume").await.unwrap());
+    assert!(!N5AsyncReader::dataset_exists(&reader, "missing").await.unwrap());
+    assert!(!N5AsyncReader::exists(&reader, "missing").await.unwrap());
+}
+
+#[wasm_bindgen_test]
+async fn test_plain_paths_verbatim() {
+    let container = MockContainer::compatible();
+    let data_attrs = dataset_attributes(&[4, 3], &[2, 2], "uint16");
+    container.add_dataset("t0:ch1,a=b", &data_attrs);
+    container.add_dataset("already%20encoded", &data_attrs);
+    container.install();
+    let reader = N5HTTPFetch::open_reader(BASE_URL).await.unwrap();
+
+    assert!(N5AsyncReader::dataset_exists(&reader, "t0:ch1,a=b").await.unwrap());
+    assert!(N5AsyncReader::dataset_exists(&reader, "already%20encoded").await.unwrap());
+}
+
+#[wasm_bindgen_test]
+async fn test_dataset_attributes() {
+    let (reader, data_attrs) = open_volume().await;
+
+    let read_attrs = N5AsyncReader::get_dataset_attributes(&reader, "volume").await.unwrap();
+    assert_eq!(
+        serde_json::to_value(read_attrs).unwrap(),
+        serde_json::to_value(data_attrs).unwrap());
+    assert!(N5AsyncReader::get_dataset_attributes(&reader, "missing").await.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_read_block() {
+    let (reader, data_attrs) = open_volume().await;
+
+    let block = N5AsyncReader::read_block::<u16>(&reader, "volume", &data_attrs, vec![0, 1].into()).await
+        .unwrap()
+        .expect("Block should exist");
+    assert_eq!(block.get_size(), &[2, 2]);
+    assert_eq!(block.get_grid_position(), &[0, 1]);
+    assert_eq!(block.get_data(), &[1, 2, 3, 4]);
+}
+
+#[wasm_bindgen_test]
+async fn test_read_block_missing() {
+    let (reader, data_attrs) = open_volume().await;
+
+    let block = N5AsyncReader::read_block::<u16>(&reader, "volume", &data_attrs, vec![1, 1].into()).await
+        .unwrap();
+    assert!(block.is_none());
+}
+
+#[wasm_bindgen_test]
+async fn test_block_etag() {
+    let (reader, data_attrs) = open_volume().await;
+
+    let etag = N5AsyncEtagReader::block_etag(&reader, "volume", &data_attrs, vec![0, 1].into()).await.unwrap();
+    assert_eq!(etag.as_ref().map(String::as_str), Some("\"abc\""));
+
+    let missing = N5AsyncEtagReader::block_etag(&reader, "volume", &data_attrs, vec![1, 1].into()).await.unwrap();
+    assert!(missing.is_none());
+}
+
+#[wasm_bindgen_test]
+async fn test_read_block_with_etag() {
+    let (reader, data_attrs) = open_volume().await;
+
+    let (block, etag) = N5AsyncEtagReader::read_block_with_etag::<u16>(&reader, "volume", &data_attrs, vec![0, 1].into())
+        .await
+        .unwrap()
+        .expect("Block should exist");
+    assert_eq!(block.get_data(), &[1, 2, 3, 4]);
+    assert_eq!(etag.as_ref().map(String::as_str), Some("\"abc\""));
+}
+
+/// Serve a block of every data type, then read it back both through the Rust
+/// trait and through the wrapped block class of the Promise API.
+macro_rules! data_type_tests {
+    ($($test_name:ident: $d_type:ty, $d_name:expr;)*) => {
+        $(
+            #[wasm_bindgen_test]
+            async fn $test_name() {
+                let container = MockContainer::compatible();
+                let data_attrs = dataset_attributes(&[3, 2], &[3, 2], $d_name);
+                container.add_dataset("typed", &data_attrs);
+                let data: Vec<$d_type> = (0..6).map(|i| i as $d_type).collect();
+                let block = VecDataBlock::<$d_type>::new(
+                    vec![3, 2].into(),
+                    vec![0, 0].into(),
+                    data.clone());
+                container.add_block("typed", &data_attrs, &block, None);
+                container.install();
+
+                let reader = N5HTTPFetch::open_reader(BASE_URL).await.unwrap();
+                let read = N5AsyncReader::read_block::<$d_type>(&reader, "typed", &data_attrs, vec![0, 0].into())
+                    .await
+                    .unwrap()
+                    .expect("Block should exist");
+                assert_eq!(read.get_data(), &data[..]);
+
+                let wrapped = N5PromiseReader::get_dataset_attributes(&reader, "typed");
+                let wrapped = await_promise(wrapped).await.unwrap();
+                assert_eq!(
+                    call_method(&wrapped, "get_data_type").as_string().unwrap(),
+                    $d_name);
+
+                let attrs_json = call_method(&wrapped, "to_json");
+                let wrapped_attrs = n5_wasm::wrapped::DatasetAttributes::from_json(attrs_json.unchecked_ref());
+                let js_block = await_promise(N5PromiseReader::read_block(
+                        &reader, "typed", &wrapped_attrs, vec![0, 0].into()))
+                    .await
+                    .unwrap();
+                assert_eq!(get_property(&js_block, "numElements"), JsValue::from(6));
+                assert_eq!(get_property(&js_block, "dtype").as_string().unwrap(), $d_name);
+                assert_eq!(js_sys::Reflect::get(&get_property(&js_block, "data"), &"length".into()).unwrap(),
+                    JsValue::from(6));
+            }
+        )*
+    }
+}
+
+data_type_tests! {
+    test_data_type_uint8: u8, "uint8";
+    test_data_type_uint16: u16, "uint16";
+    test_data_type_uint32: u32, "uint32";
+    test_data_type_uint64: u64, "uint64";
+    test_data_type_int8: i8, "int8";
+    test_data_type_int16: i16, "int16";
+    test_data_type_int32: i32, "int32";
+    test_data_type_int64: i64, "int64";
+    test_data_type_float32: f32, "float32";
+    test_data_type_float64: f64, "float64";
+}
+
+fn root_attributes() -> Vec<u8> {
+    serde_json::to_vec(&serde_json::json!({ "n5": n5::VERSION.to_string() })).unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn test_gcs_location() {
+    let container = MockContainer::new("");
+    container.add_url(
+        "https://storage.googleapis.com/bucket/root/attributes.json",
+        &root_attributes(),
+        None);
+    container.add_url(
+        "https://storage.googleapis.com/storage/v1/b/bucket/o\
+            ?prefix=root%2F&delimiter=%2F&fields=prefixes%2CnextPageToken",
+        &serde_json::to_vec(&serde_json::json!({ "prefixes": ["root/b/", "root/a/"] })).unwrap(),
+        None);
+    container.install();
+
+    let reader = N5HTTPFetch::open_reader("gs://bucket/root").await.unwrap();
+    assert_eq!(N5AsyncReader::list(&reader, "").await.unwrap(), vec!["a", "b"]);
+}
+
+#[wasm_bindgen_test]
+async fn test_gcs_forbidden_block() {
+    let container = MockContainer::new("");
+    container.add_url(
+        "https://storage.googleapis.com/bucket/root/attributes.json",
+        &root_attributes(),
+        None);
+    container.add_url_error("https://storage.googleapis.com/bucket/root/volume/0", 403);
+    container.add_url_error("https://storage.googleapis.com/bucket/root/volume/1", 500);
+    container.install();
+
+    // Public buckets without list permission answer 403 for missing objects.
+    let reader = N5HTTPFetch::open_reader("gs://bucket/root").await.unwrap();
+    let data_attrs = dataset_attributes(&[4], &[2], "uint8");
+    assert!(N5AsyncReader::read_block::<u8>(&reader, "volume", &data_attrs, vec![0].into())
+        .await
+        .unwrap()
+        .is_none());
+    assert!(N5AsyncReader::read_block::<u8>(&reader, "volume", &data_attrs, vec![1].into())
+        .await
+        .is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_azure_location() {
+    let container = MockContainer::new("");
+    container.add_url(
+        "https://account.blob.core.windows.net/data/root/attributes.json?sv=1&sig=x",
+        &root_attributes(),
+        None);
+    container.add_url(
+        "https://account.blob.core.windows.net/data\
+            ?restype=container&comp=list&prefix=root%2Fgroup%2F&delimiter=%2F&sv=1&sig=x",
+        b"<EnumerationResults><Blobs>\
+            <BlobPrefix><Name>root/group/volume/</Name></BlobPrefix>\
+            </Blobs><NextMarker /></EnumerationResults>",
+        None);
+    container.install();
+
+    let reader = N5HTTPFetch::open_reader(
+        "https://account.blob.core.windows.net/data/root/?sv=1&sig=x").await.unwrap();
+    assert_eq!(N5AsyncReader::list(&reader, "group").await.unwrap(), vec!["volume"]);
+}
+
+#[wasm_bindgen_test]
+async fn test_mirror_failover() {
+    let container = MockContainer::compatible();
+    container.add_json("mirror/attributes.json", &serde_json::json!({ "n5": n5::VERSION.to_string() }));
+    let data_attrs = dataset_attributes(&[2], &[2], "uint8");
+    container.add_dataset("volume", &data_attrs);
+    container.add_dataset("mirror/volume", &data_attrs);
+    let block = VecDataBlock::<u8>::new(vec![2].into(), vec![0].into(), vec![7, 8]);
+    container.add_error("volume/0", 503);
+    container.add_block("mirror/volume", &data_attrs, &block, None);
+    container.install();
+
+    let mirror = format!("{}/mirror", BASE_URL);
+    let reader = N5HTTPFetch::open_reader_with_mirrors(
+            &[BASE_URL, &mirror],
+            None,
+            std::rc::Rc::new(n5_wasm::transport::GlobalFetch))
+        .await
+        .unwrap();
+
+    // Reads start from alternating mirrors, and both reach the mirror.
+    for _ in 0..2 {
+        let read = N5AsyncReader::read_block::<u8>(&reader, "volume", &data_attrs, vec![0].into())
+            .await
+            .unwrap()
+            .expect("Block should exist");
+        assert_eq!(read.get_data(), &[7, 8]);
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_mirror_mismatch() {
+    let container = MockContainer::compatible();
+    container.add_json("mirror/attributes.json", &serde_json::json!({
+        "n5": n5::VERSION.to_string(),
+        "extra": true,
+    }));
+    container.install();
+
+    let mirror = format!("{}/mirror", BASE_URL);
+    assert!(N5HTTPFetch::open_reader_with_mirrors(
+            &[BASE_URL, &mirror],
+            None,
+            std::rc::Rc::new(n5_wasm::transport::GlobalFetch))
+        .await
+        .is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_mirror_outage() {
+    let container = MockContainer::compatible();
+    container.add_json("mirror/attributes.json", &serde_json::json!({ "n5": n5::VERSION.to_string() }));
+    let data_attrs = dataset_attributes(&[4], &[2], "uint8");
+    container.add_dataset("volume", &data_attrs);
+    container.add_dataset("mirror/volume", &data_attrs);
+    container.add_error("volume/0", 503);
+    container.add_error("mirror/volume/0", 500);
+    container.add_error("volume/1", 403);
+    container.install();
+
+    let mirror = format!("{}/mirror", BASE_URL);
+    let reader = N5HTTPFetch::open_reader_with_mirrors(
+            &[BASE_URL, &mirror],
+            None,
+            std::rc::Rc::new(n5_wasm::transport::GlobalFetch))
+        .await
+        .unwrap();
+
+    // Only blocks that are not found are missing, not those no mirror could
+    // serve.
+    assert!(N5AsyncReader::read_block::<u8>(&reader, "volume", &data_attrs, vec![0].into())
+        .await
+        .is_err());
+    assert!(N5AsyncReader::read_block::<u8>(&reader, "volume", &data_attrs, vec![1].into())
+        .await
+        .is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_read_varlength_block() {
+    let container = MockContainer::compatible();
+    let data_attrs = dataset_attributes(&[4, 4], &[2, 2], "uint16");
+    container.add_dataset("labels", &data_attrs);
+    // Header: varlength mode, 2 dimensions, size [2, 2] and 3 elements,
+    // followed by raw big endian data.
+    container.add_file("labels/1/0", &[
+        0, 1, 0, 2,
+        0, 0, 0, 2, 0, 0, 0, 2,
+        0, 0, 0, 3,
+        0, 5, 0, 6, 0, 7,
+    ], None);
+    container.install();
+
+    let reader = N5HTTPFetch::open_reader(BASE_URL).await.unwrap();
+    let (block, mode) = N5AsyncReader::read_block_with_mode::<u16>(&reader, "labels", &data_attrs, vec![1, 0].into())
+        .await
+        .unwrap()
+        .expect("Block should exist");
+    assert_eq!(block.get_size(), &[2, 2]);
+    assert_eq!(block.get_data(), &[5, 6, 7]);
+    assert_eq!(mode, n5_wasm::BlockMode::Varlength);
+
+    let wrapped_attrs = n5_wasm::wrapped::DatasetAttributes::from_json(
+        JsValue::from_serde(&data_attrs).unwrap().unchecked_ref());
+    let js_block = await_promise(N5PromiseReader::read_block(
+            &reader, "labels", &wrapped_attrs, vec![1, 0].into()))
+        .await
+        .unwrap();
+    assert_eq!(get_property(&js_block, "numElements"), JsValue::from(3));
+    assert_eq!(get_property(&js_block, "mode"), JsValue::from(1));
+}
+
+#[wasm_bindgen_test]
+async fn test_read_full_varlength_block() {
+    let container = MockContainer::compatible();
+    let data_attrs = dataset_attributes(&[2], &[2], "uint8");
+    container.add_dataset("labels", &data_attrs);
+    // A varlength header whose element count happens to fill the block.
+    container.add_file("labels/0", &[
+        0, 1, 0, 1,
+        0, 0, 0, 2,
+        0, 0, 0, 2,
+        8, 9,
+    ], None);
+    container.install();
+
+    let reader = N5HTTPFetch::open_reader(BASE_URL).await.unwrap();
+    let (block, mode) = N5AsyncReader::read_block_with_mode::<u8>(&reader, "labels", &data_attrs, vec![0].into())
+        .await
+        .unwrap()
+        .expect("Block should exist");
+    assert_eq!(block.get_data(), &[8, 9]);
+    assert_eq!(mode, n5_wasm::BlockMode::Varlength);
+}
@@ -0,0 +1,145 @@
+use wasm_bindgen_test::*;
+
+use n5::prelude::*;
+use n5_wasm::{
+    N5AsyncEtagReader,
+    N5AsyncReader,
+    N5AsyncWriter,
+};
+use n5_wasm::in_memory::N5InMemory;
+
+mod common;
+use common::*;
+
+
+#[wasm_bindgen_test]
+async fn test_version() {
+    let container = N5InMemory::new();
+
+    assert_eq!(
+        N5AsyncReader::get_version(&container).await.unwrap().to_string(),
+        n5::VERSION.to_string());
+}
+
+#[wasm_bindgen_test]
+async fn test_groups() {
+    let container = N5InMemory::new();
+    N5AsyncWriter::create_group(&container, "a/b/c").await.unwrap();
+    N5AsyncWriter::create_group(&container, "a/d").await.unwrap();
+
+    assert!(N5AsyncReader::exists(&container, "a/b").await.unwrap());
+    assert!(!N5AsyncReader::exists(&container, "b").await.unwrap());
+    assert_eq!(N5AsyncReader::list(&container, "a").await.unwrap(), vec!["b", "d"]);
+    assert_eq!(N5AsyncReader::list(&container, "").await.unwrap(), vec!["a"]);
+
+    N5AsyncWriter::remove(&container, "a/b").await.unwrap();
+    assert!(!N5AsyncReader::exists(&container, "a/b/c").await.unwrap());
+    assert_eq!(N5AsyncReader::list(&container, "a").await.unwrap(), vec!["d"]);
+
+    assert!(N5AsyncWriter::remove(&container, "").await.is_err());
+    assert!(N5AsyncWriter::remove(&container, "/").await.is_err());
+    assert!(N5AsyncReader::exists(&container, "a/d").await.unwrap());
+    assert!(N5AsyncReader::get_version(&container).await.is_ok());
+}
+
+#[wasm_bindgen_test]
+async fn test_attributes() {
+    let container = N5InMemory::new();
+    let mut attributes = serde_json::Map::new();
+    attributes.insert("foo".into(), serde_json::json!(1));
+    attributes.insert("bar".into(), serde_json::json!("a"));
+    N5AsyncWriter::set_attributes(&container, "group", attributes).await.unwrap();
+
+    let mut update = serde_json::Map::new();
+    update.insert("foo".into(), serde_json::json!([2]));
+    N5AsyncWriter::set_attributes(&container, "group", update).await.unwrap();
+
+    assert_eq!(
+        N5AsyncReader::list_attributes(&container, "group").await.unwrap(),
+        serde_json::json!({ "foo": [2], "bar": "a" }));
+}
+
+#[wasm_bindgen_test]
+async fn test_blocks() {
+    let container = N5InMemory::new();
+    let data_attrs = dataset_attributes(&[5, 4], &[3, 3], "float32");
+    N5AsyncWriter::create_dataset(&container, "volume", &data_attrs).await.unwrap();
+    assert!(N5AsyncReader::dataset_exists(&container, "volume").await.unwrap());
+    let read_attrs = N5AsyncReader::get_dataset_attributes(&container, "volume").await.unwrap();
+    assert_eq!(
+        serde_json::to_value(read_attrs).unwrap(),
+        serde_json::to_value(&data_attrs).unwrap());
+
+    let block = VecDataBlock::<f32>::new(
+        vec![2, 3].into(),
+        vec![1, 0].into(),
+        vec![0.5, 1.5, 2.5, 3.5, 4.5, 5.5]);
+    N5AsyncWriter::write_block(&container, "volume", &data_attrs, &block).await.unwrap();
+
+    let (read, etag) = N5AsyncEtagReader::read_block_with_etag::<f32>(&container, "volume", &data_attrs, vec![1, 0].into())
+        .await
+        .unwrap()
+        .expect("Block should exist");
+    assert_eq!(read.get_data(), block.get_data());
+    assert_eq!(
+        N5AsyncEtagReader::block_etag(&container, "volume", &data_attrs, vec![1, 0].into()).await.unwrap(),
+        etag);
+
+    N5AsyncWriter::write_block(&container, "volume", &data_attrs, &block).await.unwrap();
+    assert_ne!(
+        N5AsyncEtagReader::block_etag(&container, "volume", &data_attrs, vec![1, 0].into()).await.unwrap(),
+        etag);
+
+    assert!(N5AsyncWriter::delete_block(&container, "volume", &[1, 0]).await.unwrap());
+    assert!(!N5AsyncWriter::delete_block(&container, "volume", &[1, 0]).await.unwrap());
+    assert!(N5AsyncReader::read_block::<f32>(&container, "volume", &data_attrs, vec![1, 0].into())
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[wasm_bindgen_test]
+async fn test_varlength_blocks() {
+    let container = N5InMemory::new();
+    let data_attrs = dataset_attributes(&[10], &[4], "uint64");
+    N5AsyncWriter::create_dataset(&container, "lists", &data_attrs).await.unwrap();
+
+    let block = VecDataBlock::<u64>::new(vec![4].into(), vec![0].into(), vec![1, 2, 3, 4, 5, 6]);
+    N5AsyncWriter::write_block(&container, "lists", &data_attrs, &block).await.unwrap();
+
+    let (read, mode) = N5AsyncReader::read_block_with_mode::<u64>(&container, "lists", &data_attrs, vec![0].into())
+        .await
+        .unwrap()
+        .expect("Block should exist");
+    assert_eq!(mode, n5_wasm::BlockMode::Varlength);
+    assert_eq!(read.get_size(), &[4]);
+    assert_eq!(read.get_data(), block.get_data());
+}
+
+#[wasm_bindgen_test]
+async fn test_read_block_dyn() {
+    let container = N5InMemory::new();
+    let data_attrs = dataset_attributes(&[2, 2], &[2, 2], "int16");
+    N5AsyncWriter::create_dataset(&container, "volume", &data_attrs).await.unwrap();
+    let block = VecDataBlock::<i16>::new(vec![2, 2].into(), vec![0, 0].into(), vec![-1, 0, 1, 2]);
+    N5AsyncWriter::write_block(&container, "volume", &data_attrs, &block).await.unwrap();
+
+    let (read, mode) = N5AsyncReader::read_block_dyn(&container, "volume", &data_attrs, vec![0, 0].into())
+        .await
+        .unwrap()
+        .expect("Block should exist");
+    assert_eq!(mode, n5_wasm::BlockMode::Default);
+    assert_eq!(read.get_data_type(), DataType::INT16);
+    assert_eq!(read.get_size(), &[2, 2]);
+    match read {
+        n5_wasm::DynVecDataBlock::INT16(read) => assert_eq!(read.get_data(), block.get_data()),
+        _ => panic!("Block should have the dataset's data type"),
+    }
+
+    let (read, _mode, etag) = N5AsyncEtagReader::read_block_with_etag_dyn(&container, "volume", &data_attrs, vec![0, 0].into())
+        .await
+        .unwrap()
+        .expect("Block should exist");
+    assert_eq!(read.get_num_elements(), 4);
+    assert!(etag.is_some());
+}
@@ -0,0 +1,60 @@
+use wasm_bindgen_test::*;
+
+use n5::prelude::*;
+use n5_wasm::in_memory::N5InMemory;
+use n5_wasm::labels::{
+    block_labels,
+    LabelInfo,
+    N5AsyncLabelReader,
+};
+
+mod common;
+use common::*;
+
+
+/// A 4x2 segmentation in 2x2 blocks:
+///
+/// ```text
+/// 0 7 | 7 9
+/// 3 7 | 3 3
+/// ```
+async fn segmentation() -> (N5InMemory, DatasetAttributes) {
+    let rows = [[0u64, 7, 7, 9], [3, 7, 3, 3]];
+    in_memory_dataset(
+        "labels",
+        dataset_attributes(&[4, 2], &[2, 2], "uint64"),
+        &[],
+        |p| rows[p[1] as usize][p[0] as usize],
+    ).await
+}
+
+#[wasm_bindgen_test]
+fn test_block_labels() {
+    let data_attrs = dataset_attributes(&[4, 2], &[2, 2], "uint32");
+    let block = VecDataBlock::<u32>::new(vec![2, 2].into(), vec![1, 0].into(), vec![7, 9, 3, 3]);
+
+    let summary = block_labels(&data_attrs, &block);
+    assert_eq!(summary.labels.keys().copied().collect::<Vec<_>>(), vec![3, 7, 9]);
+    assert_eq!(summary.labels[&3], LabelInfo { count: 2, min: vec![2, 1], max: vec![3, 1] });
+    assert_eq!(summary.labels[&9], LabelInfo { count: 1, min: vec![3, 0], max: vec![3, 0] });
+}
+
+#[wasm_bindgen_test]
+async fn test_region_labels() {
+    let (container, data_attrs) = segmentation().await;
+
+    let summary = N5AsyncLabelReader::region_labels::<u64>(
+            &container, "labels", &data_attrs, &[0, 0], &[4, 2])
+        .await
+        .unwrap();
+    assert_eq!(summary.labels.len(), 3);
+    assert_eq!(summary.labels[&7], LabelInfo { count: 3, min: vec![1, 0], max: vec![2, 1] });
+    assert_eq!(summary.labels[&3], LabelInfo { count: 3, min: vec![0, 1], max: vec![3, 1] });
+
+    let summary = N5AsyncLabelReader::region_labels::<u64>(
+            &container, "labels", &data_attrs, &[1, 1], &[2, 5])
+        .await
+        .unwrap();
+    assert_eq!(summary.labels.keys().copied().collect::<Vec<_>>(), vec![3, 7]);
+    assert_eq!(summary.labels[&3], LabelInfo { count: 1, min: vec![2, 1], max: vec![2, 1] });
+}
@@ -0,0 +1,100 @@
+use wasm_bindgen_test::*;
+
+use n5_wasm::N5AsyncReader;
+use n5_wasm::http_fetch::N5HTTPFetch;
+use n5_wasm::in_memory::N5InMemory;
+use n5_wasm::overlay::{
+    Layer,
+    N5Overlay,
+};
+use n5_wasm::object::{
+    N5AsyncObjectReader,
+    N5AsyncObjectWriter,
+    ObjectDatasetAttributes,
+};
+
+mod common;
+use common::*;
+
+
+fn object_attributes(compression: &str) -> ObjectDatasetAttributes {
+    ObjectDatasetAttributes::new(
+        vec![10, 10],
+        vec![5, 5],
+        serde_json::from_value(serde_json::json!({ "type": compression })).unwrap())
+}
+
+#[wasm_bindgen_test]
+async fn test_object_round_trip() {
+    for compression in &["raw", "gzip"] {
+        let container = N5InMemory::new();
+        let data_attrs = object_attributes(compression);
+        N5AsyncObjectWriter::create_object_dataset(&container, "meta", &data_attrs).await.unwrap();
+
+        let read_attrs = N5AsyncObjectReader::get_object_dataset_attributes(&container, "meta").await.unwrap();
+        assert_eq!(read_attrs.get_block_size(), &[5, 5]);
+        // Object datasets are not numeric datasets.
+        assert!(N5AsyncReader::get_dataset_attributes(&container, "meta").await.is_err());
+
+        let data = br#"{"segments": [1, 2, 3]}"#;
+        N5AsyncObjectWriter::write_object_block(&container, "meta", &data_attrs, &[1, 0], data).await.unwrap();
+        let read = N5AsyncObjectReader::read_object_block(&container, "meta", &data_attrs, &[1, 0])
+            .await
+            .unwrap()
+            .expect("Block should exist");
+        assert_eq!(&read[..], &data[..]);
+        assert!(N5AsyncObjectReader::read_object_block(&container, "meta", &data_attrs, &[0, 0])
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_object_block_over_http() {
+    let container = MockContainer::compatible();
+    container.add_json("meta/attributes.json", &serde_json::json!({
+        "dimensions": [4],
+        "blockSize": [4],
+        "dataType": "object",
+        "compression": { "type": "raw" },
+    }));
+    container.add_file("meta/0", &[0, 2, 0, 0, 0, 3, b'a', b'b', b'c'], None);
+    container.install();
+
+    let reader = N5HTTPFetch::open_reader(BASE_URL).await.unwrap();
+    let data_attrs = N5AsyncObjectReader::get_object_dataset_attributes(&reader, "meta").await.unwrap();
+    let read = N5AsyncObjectReader::read_object_block(&reader, "meta", &data_attrs, &[0])
+        .await
+        .unwrap()
+        .expect("Block should exist");
+    assert_eq!(&read[..], b"abc");
+}
+
+#[wasm_bindgen_test]
+async fn test_object_block_through_overlay() {
+    let data_attrs = object_attributes("raw");
+    let base = N5InMemory::new();
+    N5AsyncObjectWriter::create_object_dataset(&base, "meta", &data_attrs).await.unwrap();
+    N5AsyncObjectWriter::write_object_block(&base, "meta", &data_attrs, &[0, 0], b"base").await.unwrap();
+    N5AsyncObjectWriter::write_object_block(&base, "meta", &data_attrs, &[1, 0], b"base").await.unwrap();
+    let edits = N5InMemory::new();
+    N5AsyncObjectWriter::create_object_dataset(&edits, "meta", &data_attrs).await.unwrap();
+    N5AsyncObjectWriter::write_object_block(&edits, "meta", &data_attrs, &[0, 0], b"edit").await.unwrap();
+
+    let overlay = N5Overlay::new();
+    overlay.push(Layer::Memory(base));
+    let edit_layer = overlay.push(Layer::Memory(edits));
+
+    let read = |grid_position: &'static [u64]| {
+        let (overlay, data_attrs) = (&overlay, &data_attrs);
+        async move {
+            N5AsyncObjectReader::read_object_block(overlay, "meta", data_attrs, grid_position).await.unwrap()
+        }
+    };
+    assert_eq!(read(&[0, 0]).await.as_deref(), Some(&b"edit"[..]));
+    assert_eq!(read(&[1, 0]).await.as_deref(), Some(&b"base"[..]));
+
+    overlay.add_tombstone(edit_layer, "meta", &[1, 0]).await.unwrap();
+    assert_eq!(read(&[1, 0]).await, None);
+}
@@ -0,0 +1,177 @@
+use wasm_bindgen_test::*;
+
+use n5::prelude::*;
+use n5_wasm::{
+    N5AsyncReader,
+    N5AsyncWriter,
+};
+use n5_wasm::in_memory::N5InMemory;
+use n5_wasm::overlay::{
+    BlockProvenance,
+    Layer,
+    N5Overlay,
+};
+use n5_wasm::s3::{
+    N5S3,
+    S3Config,
+};
+
+mod common;
+use common::*;
+
+
+fn block(grid_position: u64, value: u8) -> VecDataBlock<u8> {
+    VecDataBlock::new(vec![2].into(), vec![grid_position].into(), vec![value; 2])
+}
+
+/// A base layer with blocks 0 and 1 and an edit layer replacing block 0.
+async fn stacked() -> (N5Overlay, DatasetAttributes) {
+    let data_attrs = dataset_attributes(&[6], &[2], "uint8");
+
+    let base = N5InMemory::new();
+    N5AsyncWriter::create_dataset(&base, "seg", &data_attrs).await.unwrap();
+    N5AsyncWriter::create_group(&base, "base_only").await.unwrap();
+    N5AsyncWriter::write_block(&base, "seg", &data_attrs, &block(0, 1)).await.unwrap();
+    N5AsyncWriter::write_block(&base, "seg", &data_attrs, &block(1, 1)).await.unwrap();
+
+    let edits = N5InMemory::new();
+    N5AsyncWriter::create_dataset(&edits, "seg", &data_attrs).await.unwrap();
+    N5AsyncWriter::create_group(&edits, "edits_only").await.unwrap();
+    let mut attributes = serde_json::Map::new();
+    attributes.insert("editor".into(), serde_json::json!("me"));
+    N5AsyncWriter::set_attributes(&edits, "seg", attributes).await.unwrap();
+    N5AsyncWriter::write_block(&edits, "seg", &data_attrs, &block(0, 2)).await.unwrap();
+
+    let overlay = N5Overlay::new();
+    assert_eq!(overlay.push(Layer::Memory(base)), 0);
+    assert_eq!(overlay.push(Layer::Memory(edits)), 1);
+
+    (overlay, data_attrs)
+}
+
+#[wasm_bindgen_test]
+async fn test_shadowing() {
+    let (overlay, data_attrs) = stacked().await;
+
+    let (block, provenance) = overlay.read_block_with_provenance::<u8>("seg", &data_attrs, vec![0].into())
+        .await
+        .unwrap();
+    assert_eq!(block.unwrap().get_data(), &[2, 2]);
+    assert_eq!(provenance, BlockProvenance::Layer { layer: 1 });
+
+    let (block, provenance) = overlay.read_block_with_provenance::<u8>("seg", &data_attrs, vec![1].into())
+        .await
+        .unwrap();
+    assert_eq!(block.unwrap().get_data(), &[1, 1]);
+    assert_eq!(provenance, BlockProvenance::Layer { layer: 0 });
+
+    let (block, provenance) = overlay.read_block_with_provenance::<u8>("seg", &data_attrs, vec![2].into())
+        .await
+        .unwrap();
+    assert!(block.is_none());
+    assert_eq!(provenance, BlockProvenance::Missing);
+}
+
+#[wasm_bindgen_test]
+async fn test_tombstones() {
+    let (overlay, data_attrs) = stacked().await;
+
+    assert!(overlay.add_tombstone(1, "seg", &[1]).await.unwrap());
+    assert!(!overlay.add_tombstone(1, "seg", &[1]).await.unwrap());
+    let (block, provenance) = overlay.read_block_with_provenance::<u8>("seg", &data_attrs, vec![1].into())
+        .await
+        .unwrap();
+    assert!(block.is_none());
+    assert_eq!(provenance, BlockProvenance::Tombstone { layer: 1 });
+
+    // A tombstone only masks layers below its own.
+    assert!(overlay.add_tombstone(0, "seg", &[0]).await.unwrap());
+    assert!(N5AsyncReader::read_block::<u8>(&overlay, "seg", &data_attrs, vec![0].into())
+        .await
+        .unwrap()
+        .is_some());
+
+    assert!(overlay.remove_tombstone(1, "seg", &[1]).await.unwrap());
+    assert!(N5AsyncReader::read_block::<u8>(&overlay, "seg", &data_attrs, vec![1].into())
+        .await
+        .unwrap()
+        .is_some());
+    assert!(overlay.add_tombstone(2, "seg", &[1]).await.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_tombstones_persist() {
+    let data_attrs = dataset_attributes(&[6], &[2], "uint8");
+    let base = N5InMemory::new();
+    N5AsyncWriter::create_dataset(&base, "seg", &data_attrs).await.unwrap();
+    N5AsyncWriter::write_block(&base, "seg", &data_attrs, &block(1, 1)).await.unwrap();
+    let edits = N5InMemory::new();
+
+    let overlay = N5Overlay::new();
+    overlay.push(Layer::Memory(base.clone()));
+    overlay.push(Layer::Memory(edits.clone()));
+    assert!(overlay.add_tombstone(1, "seg", &[1]).await.unwrap());
+
+    // The tombstone is saved in the edits layer's own container.
+    let attributes = N5AsyncReader::list_attributes(&edits, "seg").await.unwrap();
+    assert_eq!(attributes["overlayTombstones"], serde_json::json!([[1]]));
+
+    let reloaded = N5Overlay::new();
+    reloaded.push(Layer::Memory(base));
+    reloaded.push(Layer::Memory(edits));
+    let (block, provenance) = reloaded.read_block_with_provenance::<u8>("seg", &data_attrs, vec![1].into())
+        .await
+        .unwrap();
+    assert!(block.is_none());
+    assert_eq!(provenance, BlockProvenance::Tombstone { layer: 1 });
+}
+
+#[wasm_bindgen_test]
+async fn test_tombstones_read_failure() {
+    let container = MockContainer::new("");
+    container.add_json("bucket/attributes.json", &serde_json::json!({ "n5": n5::VERSION.to_string() }));
+    container.add_error("bucket/seg/attributes.json", 503);
+    container.install();
+    let edits = N5S3::open_reader(S3Config {
+        endpoint: BASE_URL.into(),
+        region: "us-east-1".into(),
+        bucket: "bucket".into(),
+        prefix: "".into(),
+        path_style: true,
+        credentials: None,
+    }).await.unwrap();
+
+    let data_attrs = dataset_attributes(&[6], &[2], "uint8");
+    let overlay = N5Overlay::new();
+    overlay.push(Layer::S3(edits));
+
+    // Failing to read the stored tombstones must not be mistaken for there
+    // being none, which would overwrite them with only the new one.
+    assert!(overlay.add_tombstone(0, "seg", &[2]).await.is_err());
+
+    let mut attributes = serde_json::to_value(&data_attrs).unwrap();
+    attributes["overlayTombstones"] = serde_json::json!([[1]]);
+    container.add_json("bucket/seg/attributes.json", &attributes);
+
+    assert!(overlay.add_tombstone(0, "seg", &[2]).await.unwrap());
+    for grid_position in 1..3 {
+        let (_, provenance) = overlay.read_block_with_provenance::<u8>("seg", &data_attrs, vec![grid_position].into())
+            .await
+            .unwrap();
+        assert_eq!(provenance, BlockProvenance::Tombstone { layer: 0 });
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_attributes_and_listing() {
+    let (overlay, _) = stacked().await;
+
+    let attributes = N5AsyncReader::list_attributes(&overlay, "seg").await.unwrap();
+    assert_eq!(attributes["editor"], serde_json::json!("me"));
+    assert_eq!(attributes["dataType"], serde_json::json!("uint8"));
+    assert!(N5AsyncReader::dataset_exists(&overlay, "seg").await.unwrap());
+
+    assert_eq!(
+        N5AsyncReader::list(&overlay, "").await.unwrap(),
+        vec!["base_only", "edits_only", "seg"]);
+}
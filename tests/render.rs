@@ -0,0 +1,130 @@
+use wasm_bindgen::Clamped;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+use n5_wasm::render::{
+    colormap_lut,
+    render,
+    render_rgba,
+    RenderOptions,
+    SegmentColors,
+};
+
+
+#[wasm_bindgen_test]
+fn test_render_window_and_gamma() {
+    let data: Vec<u16> = vec![0, 100, 150, 200, 300];
+    let mut rgba = vec![0; data.len() * 4];
+
+    render_rgba(&data, &RenderOptions::new(100., 200.), &mut rgba).unwrap();
+    let gray: Vec<u8> = rgba.chunks(4).map(|pixel| pixel[0]).collect();
+    assert_eq!(gray, vec![0, 0, 128, 255, 255]);
+    assert!(rgba.chunks(4).all(|pixel| pixel[0] == pixel[1] && pixel[1] == pixel[2] && pixel[3] == 255));
+
+    let options = RenderOptions {
+        gamma: 2.,
+        ..RenderOptions::new(100., 200.)
+    };
+    render_rgba(&data, &options, &mut rgba).unwrap();
+    assert_eq!(rgba[8], 64);
+
+    assert!(render_rgba(&data, &options, &mut rgba[4..]).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_render_colormap() {
+    let data: Vec<f32> = vec![-1., 0.5, 2.];
+    let mut rgba = vec![0; data.len() * 4];
+    let options = RenderOptions {
+        lut: colormap_lut("red"),
+        ..RenderOptions::new(0., 1.)
+    };
+
+    render_rgba(&data, &options, &mut rgba).unwrap();
+    assert_eq!(rgba, vec![
+        0, 0, 0, 255,
+        128, 0, 0, 255,
+        255, 0, 0, 255,
+    ]);
+
+    let hot = colormap_lut("hot").unwrap();
+    assert_eq!(hot.len(), 256 * 4);
+    assert_eq!(&hot[255 * 4..], &[255, 255, 255, 255]);
+    assert!(colormap_lut("viridian").is_none());
+}
+
+/// Render a pixel with options given as JS numbers.
+fn render_with(min: f64, max: f64, gamma: Option<f64>) -> Result<(), wasm_bindgen::JsValue> {
+    let options = js_sys::Object::new();
+    js_sys::Reflect::set(&options, &"min".into(), &min.into()).unwrap();
+    js_sys::Reflect::set(&options, &"max".into(), &max.into()).unwrap();
+    if let Some(gamma) = gamma {
+        js_sys::Reflect::set(&options, &"gamma".into(), &gamma.into()).unwrap();
+    }
+    let source = js_sys::Uint8Array::from(&[0u8][..]);
+    let mut rgba = vec![0; 4];
+
+    render(source.unchecked_ref(), options.unchecked_ref(), Clamped(&mut rgba))
+}
+
+#[wasm_bindgen_test]
+fn test_render_options_validation() {
+    assert!(render_with(0., 1., None).is_ok());
+    assert!(render_with(1., 1., Some(0.5)).is_ok());
+
+    assert!(render_with(0., 1., Some(-1.)).is_err());
+    assert!(render_with(0., 1., Some(0.)).is_err());
+    assert!(render_with(0., 1., Some(f64::NAN)).is_err());
+    assert!(render_with(0., 1., Some(f64::INFINITY)).is_err());
+    assert!(render_with(f64::NAN, 1., None).is_err());
+    assert!(render_with(0., f64::NAN, None).is_err());
+    assert!(render_with(f64::NEG_INFINITY, 1., None).is_err());
+    assert!(render_with(0., f64::INFINITY, None).is_err());
+    assert!(render_with(2., 1., None).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_segment_colors() {
+    let mut colors = SegmentColors::new(7);
+    assert_eq!(colors.color(0), [0; 4]);
+    assert_eq!(colors.color(42), SegmentColors::new(7).color(42));
+    assert_ne!(colors.color(42)[..3], colors.color(43)[..3]);
+    assert_ne!(colors.color(42)[..3], SegmentColors::new(8).color(42)[..3]);
+    assert_eq!(colors.color(42)[3], 255);
+
+    colors.set_equivalent(43, 42);
+    colors.selected.insert(42);
+    colors.unselected_alpha = 10;
+    assert_eq!(colors.color(43), colors.color(42));
+    assert_eq!(colors.color(42)[3], 255);
+    assert_eq!(colors.color(44)[3], 10);
+
+    // Merges are transitive, and selections follow later merges.
+    colors.set_equivalent(45, 46);
+    colors.set_equivalent(46, 43);
+    assert_eq!(colors.representative(45), 42);
+    assert_eq!(colors.color(45), colors.color(42));
+    colors.selected.insert(47);
+    colors.set_equivalent(48, 47);
+    assert_eq!(colors.color(48)[3], 255);
+    colors.deselect(48);
+    assert_eq!(colors.color(47)[3], 10);
+    assert_eq!(colors.color(43)[3], 255);
+
+    let labels: Vec<u64> = vec![0, 42, 43, 44];
+    let mut rgba = vec![0; labels.len() * 4];
+    colors.render_rgba(&labels, &mut rgba).unwrap();
+    let expected: Vec<u8> = labels.iter().flat_map(|&label| colors.color(label).to_vec()).collect();
+    assert_eq!(rgba, expected);
+}
+
+#[wasm_bindgen_test]
+fn test_segment_colors_default_alpha() {
+    let mut colors = SegmentColors::default();
+    colors.selected.insert(42);
+
+    let labels: Vec<u32> = vec![42, 43];
+    let mut rgba = vec![0; labels.len() * 4];
+    colors.render_rgba(&labels, &mut rgba).unwrap();
+    assert_eq!((rgba[3], rgba[7]), (255, 64));
+}
@@ -0,0 +1,112 @@
+use wasm_bindgen_test::*;
+
+use n5::prelude::*;
+use n5_wasm::in_memory::N5InMemory;
+use n5_wasm::reslice::{
+    Interpolation,
+    N5AsyncResliceReader,
+    Plane,
+};
+
+mod common;
+use common::*;
+
+
+fn linear(x: f32, y: f32, z: f32) -> f32 {
+    x + 10. * y + 100. * z
+}
+
+/// A 4x4x4 volume, in 2x2x2 blocks, of a linear function of the coordinates.
+async fn volume() -> (N5InMemory, DatasetAttributes) {
+    in_memory_dataset(
+        "volume",
+        dataset_attributes(&[4, 4, 4], &[2, 2, 2], "float32"),
+        &[],
+        |p| linear(p[0] as f32, p[1] as f32, p[2] as f32),
+    ).await
+}
+
+#[wasm_bindgen_test]
+async fn test_reslice_nearest() {
+    let (container, data_attrs) = volume().await;
+    let plane = Plane {
+        origin: [0.4, 1., 2.],
+        u: [2., 0., 0.],
+        v: [0., 0., 1.],
+        size: [3, 2],
+        spacing: [1.5, 1.],
+    };
+
+    let reslice = N5AsyncResliceReader::reslice::<f32>(
+            &container, "volume", &data_attrs, &plane, Interpolation::Nearest)
+        .await
+        .unwrap();
+    assert_eq!(reslice.size, [3, 2]);
+    // Samples at x = 0.4, 1.9 and 3.4, the last of which is inside the
+    // dataset's last voxel, at z = 2 and 3.
+    assert_eq!(reslice.data, vec![
+        linear(0., 1., 2.), linear(2., 1., 2.), linear(3., 1., 2.),
+        linear(0., 1., 3.), linear(2., 1., 3.), linear(3., 1., 3.),
+    ]);
+}
+
+#[wasm_bindgen_test]
+async fn test_reslice_trilinear() {
+    let (container, data_attrs) = volume().await;
+    // A diagonal plane crossing block boundaries, with samples beyond the
+    // dataset along its rows.
+    let plane = Plane {
+        origin: [0.5, 0.5, 0.5],
+        u: [1., 1., 0.],
+        v: [0., 0., 1.],
+        size: [4, 2],
+        spacing: [2f64.sqrt(), 1.25],
+    };
+
+    let reslice = N5AsyncResliceReader::reslice::<f32>(
+            &container, "volume", &data_attrs, &plane, Interpolation::Trilinear)
+        .await
+        .unwrap();
+    let expected = vec![
+        linear(0.5, 0.5, 0.5), linear(1.5, 1.5, 0.5), linear(2.5, 2.5, 0.5), 0.,
+        linear(0.5, 0.5, 1.75), linear(1.5, 1.5, 1.75), linear(2.5, 2.5, 1.75), 0.,
+    ];
+    for (sample, expected) in reslice.data.iter().zip(&expected) {
+        assert!((sample - expected).abs() < 1e-3, "{} != {}", sample, expected);
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_reslice_degenerate_plane() {
+    let (container, data_attrs) = volume().await;
+    let plane = Plane {
+        origin: [0., 0., 0.],
+        u: [0., 0., 0.],
+        v: [0., 0., 1.],
+        size: [1, 1],
+        spacing: [1., 1.],
+    };
+
+    assert!(N5AsyncResliceReader::reslice::<f32>(
+            &container, "volume", &data_attrs, &plane, Interpolation::Nearest)
+        .await
+        .is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_reslice_too_large() {
+    let (container, data_attrs) = volume().await;
+    let plane = Plane {
+        origin: [0., 0., 0.],
+        u: [1., 0., 0.],
+        v: [0., 1., 0.],
+        size: [1 << 16, 1 << 16],
+        spacing: [1., 1.],
+    };
+
+    let error = N5AsyncResliceReader::reslice::<f32>(
+            &container, "volume", &data_attrs, &plane, Interpolation::Nearest)
+        .await
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+}
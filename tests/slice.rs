@@ -0,0 +1,81 @@
+use wasm_bindgen_test::*;
+
+use n5::prelude::*;
+use n5_wasm::in_memory::N5InMemory;
+use n5_wasm::slice::{
+    N5AsyncSliceReader,
+    SliceBounds,
+};
+
+mod common;
+use common::*;
+
+
+const DIMENSIONS: [u64; 3] = [5, 4, 3];
+
+/// Value of each voxel of the test volume: its column-major linear index.
+fn voxel(x: u64, y: u64, z: u64) -> u16 {
+    (x + DIMENSIONS[0] * (y + DIMENSIONS[1] * z)) as u16
+}
+
+/// A 5x4x3 volume in 2x2x2 blocks, without the block at [0, 0, 1].
+async fn volume() -> (N5InMemory, DatasetAttributes) {
+    in_memory_dataset(
+        "volume",
+        dataset_attributes(&DIMENSIONS, &[2, 2, 2], "uint16"),
+        &[&[0, 0, 1]],
+        |p| voxel(p[0], p[1], p[2]),
+    ).await
+}
+
+#[wasm_bindgen_test]
+async fn test_read_slice_axes() {
+    let (container, data_attrs) = volume().await;
+
+    let xy = N5AsyncSliceReader::read_slice::<u16>(&container, "volume", &data_attrs, 2, 0, None)
+        .await
+        .unwrap();
+    assert_eq!(xy.plane_axes, [0, 1]);
+    assert_eq!((xy.get_width(), xy.get_height()), (5, 4));
+    let expected: Vec<u16> = (0..4).flat_map(|y| (0..5).map(move |x| voxel(x, y, 0))).collect();
+    assert_eq!(xy.data, expected);
+
+    let xz = N5AsyncSliceReader::read_slice::<u16>(&container, "volume", &data_attrs, 1, 3, None)
+        .await
+        .unwrap();
+    assert_eq!(xz.plane_axes, [0, 2]);
+    let expected: Vec<u16> = (0..3).flat_map(|z| (0..5).map(move |x| voxel(x, 3, z))).collect();
+    assert_eq!(xz.data, expected);
+
+    let yz = N5AsyncSliceReader::read_slice::<u16>(&container, "volume", &data_attrs, 0, 4, None)
+        .await
+        .unwrap();
+    assert_eq!(yz.plane_axes, [1, 2]);
+    let expected: Vec<u16> = (0..3).flat_map(|z| (0..4).map(move |y| voxel(4, y, z))).collect();
+    assert_eq!(yz.data, expected);
+}
+
+#[wasm_bindgen_test]
+async fn test_read_slice_bounds_and_missing_blocks() {
+    let (container, data_attrs) = volume().await;
+
+    // Bounds are clamped to the dataset, and the missing block reads as zeros.
+    let slice = N5AsyncSliceReader::read_slice::<u16>(
+            &container,
+            "volume",
+            &data_attrs,
+            2,
+            2,
+            Some(SliceBounds { offset: [1, 1], size: [10, 2] }))
+        .await
+        .unwrap();
+    assert_eq!(slice.bounds, SliceBounds { offset: [1, 1], size: [4, 2] });
+    assert_eq!(slice.data, vec![
+        0, voxel(2, 1, 2), voxel(3, 1, 2), voxel(4, 1, 2),
+        voxel(1, 2, 2), voxel(2, 2, 2), voxel(3, 2, 2), voxel(4, 2, 2),
+    ]);
+
+    assert!(N5AsyncSliceReader::read_slice::<u16>(&container, "volume", &data_attrs, 2, 3, None)
+        .await
+        .is_err());
+}
@@ -0,0 +1,92 @@
+use wasm_bindgen_test::*;
+
+use n5_wasm::stats::{
+    data_statistics,
+    N5AsyncStatisticsReader,
+    StatisticsOptions,
+    MAX_HISTOGRAM_BINS,
+};
+
+mod common;
+use common::*;
+
+
+#[wasm_bindgen_test]
+fn test_data_statistics() {
+    let data: Vec<f32> = vec![1., 2., 3., 4., f32::NAN];
+    let options = StatisticsOptions {
+        bins: 4,
+        percentiles: vec![0., 50., 100.],
+        ..Default::default()
+    };
+
+    let statistics = data_statistics(&data, &options).unwrap();
+    assert_eq!(statistics.count, 4);
+    assert_eq!((statistics.min, statistics.max, statistics.mean), (1., 4., 2.5));
+    assert!((statistics.std - 1.25f64.sqrt()).abs() < 1e-9);
+    assert_eq!(statistics.histogram.range, [1., 4.]);
+    assert_eq!(statistics.histogram.counts, vec![1, 1, 1, 1]);
+    assert_eq!(statistics.percentiles, vec![1., 2.5, 4.]);
+
+    let options = StatisticsOptions {
+        bins: 2,
+        range: Some([0., 2.]),
+        ..Default::default()
+    };
+    let statistics = data_statistics(&data, &options).unwrap();
+    assert_eq!(statistics.histogram.counts, vec![0, 2]);
+
+    assert!(data_statistics::<u8>(&[], &options).is_none());
+}
+
+#[wasm_bindgen_test]
+fn test_options_validation() {
+    assert!(StatisticsOptions::default().validate().is_ok());
+
+    let mut options = StatisticsOptions {
+        bins: MAX_HISTOGRAM_BINS,
+        ..Default::default()
+    };
+    assert!(options.validate().is_ok());
+    options.bins += 1;
+    assert_eq!(options.validate().unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[wasm_bindgen_test]
+async fn test_region_statistics() {
+    let (container, data_attrs) = in_memory_dataset(
+        "stats",
+        dataset_attributes(&[4, 2], &[2, 2], "uint8"),
+        &[],
+        |p| if p[0] < 2 { 10u8 } else { 20 },
+    ).await;
+
+    let options = StatisticsOptions::default();
+    let statistics = N5AsyncStatisticsReader::region_statistics::<u8>(
+            &container, "stats", &data_attrs, &[1, 0], &[2, 1], &options)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(statistics.count, 2);
+    assert_eq!((statistics.min, statistics.max, statistics.mean), (10., 20., 15.));
+    assert_eq!(statistics.blocks, 2);
+
+    let options = StatisticsOptions {
+        sample_blocks: Some(1),
+        ..Default::default()
+    };
+    let statistics = N5AsyncStatisticsReader::region_statistics::<u8>(
+            &container, "stats", &data_attrs, &[0, 0], &[4, 2], &options)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!((statistics.count, statistics.blocks), (4, 1));
+    assert_eq!(statistics.max, 10.);
+
+    let statistics = N5AsyncStatisticsReader::block_statistics::<u8>(
+            &container, "stats", &data_attrs, vec![1, 0], &StatisticsOptions::default())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!((statistics.count, statistics.mean), (4, 20.));
+}
@@ -0,0 +1,93 @@
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+use n5::prelude::*;
+use n5_wasm::{
+    N5AsyncWriter,
+    N5PromiseReader,
+};
+use n5_wasm::in_memory::N5InMemory;
+use n5_wasm::typescript::Coordinates;
+
+mod common;
+use common::*;
+
+
+fn coordinates(values: &[JsValue]) -> Coordinates {
+    JsValue::from(values.iter().collect::<js_sys::Array>()).unchecked_into()
+}
+
+#[wasm_bindgen_test]
+fn test_coordinates() {
+    let numbers = coordinates(&[JsValue::from(0), JsValue::from(7)]);
+    assert_eq!(numbers.to_vec().unwrap(), vec![0, 7]);
+
+    let bigints = Coordinates::from(vec![3, u64::MAX]);
+    assert_eq!(bigints.to_vec().unwrap(), vec![3, u64::MAX]);
+
+    assert!(coordinates(&[JsValue::from(-1)]).to_vec().is_err());
+    assert!(coordinates(&[JsValue::from(1.5)]).to_vec().is_err());
+    assert!(coordinates(&[JsValue::from("a")]).to_vec().is_err());
+    assert!(coordinates(&[JsValue::from("5")]).to_vec().is_err());
+    assert!(coordinates(&[JsValue::UNDEFINED]).to_vec().is_err());
+    assert!(coordinates(&[JsValue::NULL]).to_vec().is_err());
+    assert!(coordinates(&[js_sys::eval("-3n").unwrap()]).to_vec().is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_iter_blocks() {
+    let container = N5InMemory::new();
+    let data_attrs = dataset_attributes(&[5, 4], &[2, 2], "uint8");
+    N5AsyncWriter::create_dataset(&container, "volume", &data_attrs).await.unwrap();
+    for grid_position in &[[0, 0], [1, 0], [2, 0], [1, 1], [2, 1]] {
+        let block = VecDataBlock::<u8>::new(
+            vec![2, 2].into(),
+            grid_position.to_vec().into(),
+            vec![0; 4]);
+        N5AsyncWriter::write_block(&container, "volume", &data_attrs, &block).await.unwrap();
+    }
+
+    let wrapped_attrs = n5_wasm::wrapped::DatasetAttributes::from_json(
+        JsValue::from_serde(&data_attrs).unwrap().unchecked_ref());
+    // The region [1, 1]..[6, 4] is clamped to the dataset, and block [0, 1]
+    // is skipped since it is missing.
+    let iterator = N5PromiseReader::iter_blocks(
+            &container,
+            "volume",
+            &wrapped_attrs,
+            coordinates(&[JsValue::from(1), JsValue::from(1)]),
+            coordinates(&[JsValue::from(5), JsValue::from(3)]))
+        .unwrap();
+
+    let mut grid_positions = Vec::new();
+    loop {
+        let result = await_promise(call_method(&iterator, "next")).await.unwrap();
+        if get_property(&result, "done").is_truthy() {
+            break;
+        }
+        let block = get_property(&result, "value");
+        grid_positions.push(js_sys::BigUint64Array::from(get_property(&block, "gridPosition")).to_vec());
+    }
+
+    assert_eq!(grid_positions, vec![
+        vec![0, 0], vec![1, 0], vec![2, 0],
+        vec![1, 1], vec![2, 1],
+    ]);
+}
+
+#[wasm_bindgen_test]
+fn test_iter_blocks_wrong_dimensions() {
+    let container = N5InMemory::new();
+    let data_attrs = dataset_attributes(&[5, 4], &[2, 2], "uint8");
+    let wrapped_attrs = n5_wasm::wrapped::DatasetAttributes::from_json(
+        JsValue::from_serde(&data_attrs).unwrap().unchecked_ref());
+
+    assert!(N5PromiseReader::iter_blocks(
+            &container,
+            "volume",
+            &wrapped_attrs,
+            vec![0].into(),
+            vec![1].into())
+        .is_err());
+}
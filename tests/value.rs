@@ -0,0 +1,59 @@
+use wasm_bindgen_test::*;
+
+use n5::prelude::*;
+use n5_wasm::in_memory::N5InMemory;
+use n5_wasm::value::N5AsyncValueReader;
+
+mod common;
+use common::*;
+
+
+/// A 5x3 dataset in 2x2 blocks, without the block at [1, 1], where each
+/// voxel's value is its column-major linear index.
+async fn dataset() -> (N5InMemory, DatasetAttributes) {
+    in_memory_dataset(
+        "values",
+        dataset_attributes(&[5, 3], &[2, 2], "int32"),
+        &[&[1, 1]],
+        |p| (p[0] + 5 * p[1]) as i32,
+    ).await
+}
+
+#[wasm_bindgen_test]
+async fn test_get_value() {
+    let (container, data_attrs) = dataset().await;
+
+    let value = N5AsyncValueReader::get_value::<i32>(&container, "values", &data_attrs, &[3, 1])
+        .await
+        .unwrap();
+    assert_eq!(value, Some(8));
+
+    // The truncated block at the dataset's upper edge.
+    let value = N5AsyncValueReader::get_value::<i32>(&container, "values", &data_attrs, &[4, 2])
+        .await
+        .unwrap();
+    assert_eq!(value, Some(14));
+
+    let value = N5AsyncValueReader::get_value::<i32>(&container, "values", &data_attrs, &[2, 2])
+        .await
+        .unwrap();
+    assert_eq!(value, None);
+
+    assert!(N5AsyncValueReader::get_value::<i32>(&container, "values", &data_attrs, &[5, 0])
+        .await
+        .is_err());
+    assert!(N5AsyncValueReader::get_value::<i32>(&container, "values", &data_attrs, &[1])
+        .await
+        .is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_get_values() {
+    let (container, data_attrs) = dataset().await;
+
+    let coords = vec![vec![0, 0], vec![1, 1], vec![3, 2], vec![2, 0], vec![4, 0]];
+    let values = N5AsyncValueReader::get_values::<i32>(&container, "values", &data_attrs, &coords)
+        .await
+        .unwrap();
+    assert_eq!(values, vec![Some(0), Some(6), None, Some(2), Some(4)]);
+}
@@ -0,0 +1,307 @@
+use std::io::Write;
+use std::rc::Rc;
+
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+use n5::prelude::*;
+use n5_wasm::N5AsyncReader;
+use n5_wasm::transport::JsCallbackTransport;
+use n5_wasm::zip::N5Zip;
+
+mod common;
+use common::*;
+
+
+const SATURATED: u32 = std::u32::MAX;
+
+/// An entry of a test archive, stored uncompressed unless deflated.
+struct Entry {
+    name: String,
+    data: Vec<u8>,
+    flags: u16,
+    method: u16,
+    uncompressed_size: u32,
+}
+
+impl Entry {
+    fn new(name: &str, data: &[u8]) -> Self {
+        Entry {
+            name: name.to_owned(),
+            data: data.to_vec(),
+            flags: 0,
+            method: 0,
+            uncompressed_size: data.len() as u32,
+        }
+    }
+
+    fn deflated(name: &str, data: &[u8]) -> Self {
+        let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+
+        Entry {
+            data: encoder.finish().unwrap(),
+            method: 8,
+            ..Self::new(name, data)
+        }
+    }
+
+    fn json(name: &str, value: &serde_json::Value) -> Self {
+        Self::new(name, &serde_json::to_vec(value).unwrap())
+    }
+}
+
+/// How the end of a test archive's central directory is recorded.
+enum Layout {
+    Plain,
+    /// Saturate the 32-bit fields and record them in ZIP64 structures,
+    /// except for `malformed`, whose ZIP64 extra field overruns its header.
+    Zip64 { malformed: Option<&'static str> },
+}
+
+fn push_u16(buff: &mut Vec<u8>, value: u16) {
+    buff.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_u32(buff: &mut Vec<u8>, value: u32) {
+    buff.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_u64(buff: &mut Vec<u8>, value: u64) {
+    buff.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Build an archive of entries, followed by an archive comment.
+fn build_zip(entries: &[Entry], layout: Layout, comment: &[u8]) -> Vec<u8> {
+    let mut archive = Vec::new();
+    let mut offsets = Vec::new();
+    for entry in entries {
+        offsets.push(archive.len() as u64);
+        push_u32(&mut archive, 0x0403_4b50);
+        push_u16(&mut archive, 20);
+        push_u16(&mut archive, entry.flags);
+        push_u16(&mut archive, entry.method);
+        push_u32(&mut archive, 0);
+        push_u32(&mut archive, 0);
+        push_u32(&mut archive, entry.data.len() as u32);
+        push_u32(&mut archive, entry.uncompressed_size);
+        push_u16(&mut archive, entry.name.len() as u16);
+        push_u16(&mut archive, 0);
+        archive.extend_from_slice(entry.name.as_bytes());
+        archive.extend_from_slice(&entry.data);
+    }
+
+    let cd_offset = archive.len() as u64;
+    for (entry, &offset) in entries.iter().zip(&offsets) {
+        let zip64 = match layout {
+            Layout::Plain => None,
+            Layout::Zip64 { malformed } => Some(malformed == Some(entry.name.as_str())),
+        };
+        // Only the local header offset is saturated, so the extra field
+        // holds only it. A malformed field claims 8 bytes but has none.
+        let extra_len = match zip64 {
+            None => 0,
+            Some(false) => 12,
+            Some(true) => 4,
+        };
+
+        push_u32(&mut archive, 0x0201_4b50);
+        push_u16(&mut archive, 45);
+        push_u16(&mut archive, 45);
+        push_u16(&mut archive, entry.flags);
+        push_u16(&mut archive, entry.method);
+        push_u32(&mut archive, 0);
+        push_u32(&mut archive, 0);
+        push_u32(&mut archive, entry.data.len() as u32);
+        push_u32(&mut archive, entry.uncompressed_size);
+        push_u16(&mut archive, entry.name.len() as u16);
+        push_u16(&mut archive, extra_len);
+        push_u16(&mut archive, 0);
+        push_u16(&mut archive, 0);
+        push_u16(&mut archive, 0);
+        push_u32(&mut archive, 0);
+        push_u32(&mut archive, if zip64.is_some() { SATURATED } else { offset as u32 });
+        archive.extend_from_slice(entry.name.as_bytes());
+        if let Some(malformed) = zip64 {
+            push_u16(&mut archive, 0x0001);
+            push_u16(&mut archive, 8);
+            if !malformed {
+                push_u64(&mut archive, offset);
+            }
+        }
+    }
+    let cd_size = archive.len() as u64 - cd_offset;
+
+    let (num_entries, eocd_cd_size, eocd_cd_offset) = match layout {
+        Layout::Plain => (entries.len() as u16, cd_size as u32, cd_offset as u32),
+        Layout::Zip64 { .. } => {
+            let zip64_eocd_offset = archive.len() as u64;
+            push_u32(&mut archive, 0x0606_4b50);
+            push_u64(&mut archive, 44);
+            push_u16(&mut archive, 45);
+            push_u16(&mut archive, 45);
+            push_u32(&mut archive, 0);
+            push_u32(&mut archive, 0);
+            push_u64(&mut archive, entries.len() as u64);
+            push_u64(&mut archive, entries.len() as u64);
+            push_u64(&mut archive, cd_size);
+            push_u64(&mut archive, cd_offset);
+
+            push_u32(&mut archive, 0x0706_4b50);
+            push_u32(&mut archive, 0);
+            push_u64(&mut archive, zip64_eocd_offset);
+            push_u32(&mut archive, 1);
+
+            (std::u16::MAX, SATURATED, SATURATED)
+        },
+    };
+
+    push_u32(&mut archive, 0x0605_4b50);
+    push_u16(&mut archive, 0);
+    push_u16(&mut archive, 0);
+    push_u16(&mut archive, num_entries);
+    push_u16(&mut archive, num_entries);
+    push_u32(&mut archive, eocd_cd_size);
+    push_u32(&mut archive, eocd_cd_offset);
+    push_u16(&mut archive, comment.len() as u16);
+    archive.extend_from_slice(comment);
+
+    archive
+}
+
+fn block_bytes(data_attrs: &DatasetAttributes, block: &VecDataBlock<u8>) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    <n5::DefaultBlock as n5::DefaultBlockWriter<u8, _, _>>::write_block(
+        &mut buffer,
+        data_attrs,
+        block).unwrap();
+
+    buffer
+}
+
+/// Entries of a container with a `volume` dataset and its block 0, all
+/// below `root`.
+fn container_entries(root: &str) -> (Vec<Entry>, DatasetAttributes) {
+    let data_attrs = dataset_attributes(&[4], &[2], "uint8");
+    let block = VecDataBlock::<u8>::new(vec![2].into(), vec![0].into(), vec![3, 4]);
+    let entries = vec![
+        Entry::json(
+            &format!("{}attributes.json", root),
+            &serde_json::json!({ "n5": n5::VERSION.to_string() })),
+        Entry::json(
+            &format!("{}volume/attributes.json", root),
+            &serde_json::to_value(&data_attrs).unwrap()),
+        Entry::new(&format!("{}volume/0", root), &block_bytes(&data_attrs, &block)),
+    ];
+
+    (entries, data_attrs)
+}
+
+async fn open(archive: &[u8]) -> Result<N5Zip, std::io::Error> {
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(archive));
+    let blob = web_sys::Blob::new_with_u8_array_sequence(&parts).unwrap();
+
+    N5Zip::open_blob_reader(blob).await
+}
+
+async fn read_data(reader: &N5Zip, data_attrs: &DatasetAttributes) -> Result<Option<Vec<u8>>, std::io::Error> {
+    N5AsyncReader::read_block::<u8>(reader, "volume", data_attrs, vec![0].into()).await
+        .map(|block| block.map(|block| block.get_data().to_vec()))
+}
+
+#[wasm_bindgen_test]
+async fn test_read() {
+    let (entries, data_attrs) = container_entries("");
+    // The end of central directory record is found behind a comment.
+    let reader = open(&build_zip(&entries, Layout::Plain, b"archive comment")).await.unwrap();
+
+    assert!(N5AsyncReader::dataset_exists(&reader, "volume").await.unwrap());
+    assert_eq!(N5AsyncReader::list(&reader, "").await.unwrap(), vec!["volume"]);
+    assert_eq!(read_data(&reader, &data_attrs).await.unwrap(), Some(vec![3, 4]));
+    assert_eq!(
+        N5AsyncReader::read_block::<u8>(&reader, "volume", &data_attrs, vec![1].into()).await.unwrap(),
+        None);
+}
+
+#[wasm_bindgen_test]
+async fn test_read_zip64() {
+    let (entries, data_attrs) = container_entries("");
+    let reader = open(&build_zip(&entries, Layout::Zip64 { malformed: None }, b"")).await.unwrap();
+
+    assert_eq!(read_data(&reader, &data_attrs).await.unwrap(), Some(vec![3, 4]));
+}
+
+#[wasm_bindgen_test]
+async fn test_malformed_zip64_extra_field() {
+    let (entries, data_attrs) = container_entries("");
+    let archive = build_zip(&entries, Layout::Zip64 { malformed: Some("volume/0") }, b"");
+    let reader = open(&archive).await.unwrap();
+
+    // The overrunning field is ignored, leaving a saturated offset past the
+    // end of the archive.
+    assert!(read_data(&reader, &data_attrs).await.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_not_a_zip() {
+    assert!(open(b"not a zip archive, but long enough").await.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_root_detection() {
+    let (entries, data_attrs) = container_entries("sample.n5/");
+    let reader = open(&build_zip(&entries, Layout::Plain, b"")).await.unwrap();
+    assert_eq!(read_data(&reader, &data_attrs).await.unwrap(), Some(vec![3, 4]));
+
+    // Without root attributes, several top-level directories are ambiguous.
+    let (mut entries, _) = container_entries("a/");
+    entries.extend(container_entries("b/").0);
+    assert!(open(&build_zip(&entries, Layout::Plain, b"")).await.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_encrypted_entry() {
+    let (mut entries, data_attrs) = container_entries("");
+    entries[2].flags = 0x0001;
+    let reader = open(&build_zip(&entries, Layout::Plain, b"")).await.unwrap();
+
+    assert!(read_data(&reader, &data_attrs).await.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_range_ignored() {
+    let (entries, data_attrs) = container_entries("");
+    let archive = js_sys::Uint8Array::from(&build_zip(&entries, Layout::Plain, b"")[..]);
+    let downloads = js_sys::Array::new();
+    let serve = js_sys::Function::new_with_args("archive, downloads, url, method", r#"
+        if (method === 'HEAD') {
+            return new Response(null, { headers: { 'Content-Length': String(archive.length) } });
+        }
+        downloads.push(url);
+        return new Response(archive);
+    "#);
+    let transport = serve.bind2(&JsValue::NULL, &archive, &downloads);
+
+    let reader = N5Zip::open_url_reader(
+            "http://zip.mock/archive.zip",
+            Rc::new(JsCallbackTransport::new(transport)))
+        .await
+        .unwrap();
+    assert_eq!(read_data(&reader, &data_attrs).await.unwrap(), Some(vec![3, 4]));
+    // The archive is downloaded once rather than for every range.
+    assert_eq!(downloads.length(), 1);
+}
+
+#[wasm_bindgen_test]
+async fn test_deflated_entry_size() {
+    let (mut entries, data_attrs) = container_entries("");
+    let block = entries.pop().unwrap();
+    entries.push(Entry::deflated(&block.name, &block.data));
+    let reader = open(&build_zip(&entries, Layout::Plain, b"")).await.unwrap();
+    assert_eq!(read_data(&reader, &data_attrs).await.unwrap(), Some(vec![3, 4]));
+
+    // Entries may not decompress to more than their declared size.
+    entries.last_mut().unwrap().uncompressed_size -= 1;
+    let reader = open(&build_zip(&entries, Layout::Plain, b"")).await.unwrap();
+    assert!(read_data(&reader, &data_attrs).await.is_err());
+}